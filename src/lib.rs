@@ -0,0 +1,3 @@
+pub mod nes;
+#[cfg(feature = "debug")]
+pub mod util;
@@ -1,12 +1,106 @@
-mod nes;
+use boss_rush_nes::nes;
 #[cfg(feature = "debug")]
-mod util;
+use boss_rush_nes::util;
 
 #[cfg(feature = "debug")]
 fn main() {
-    match util::debug::debug_cpu(
-        "A2 0A 8E 00 00 A2 03 8E 01 00 AC 00 00 A9 00 18 6D 01 00 88 D0 FA 8D 02 00 EA EA EA",
-    ) {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.as_slice() {
+        // Prints a nestest.log style trace of a ROM, optionally limited to a number of instructions
+        [command, rom, rest @ ..] if command == "trace" && rest.len() <= 1 => {
+            let max_instructions = rest.first().and_then(|count| count.parse().ok());
+
+            nes::Nes::from_rom(rom)
+                .map_err(|e| e.to_string())
+                .and_then(|mut nes| {
+                    util::trace::trace(&mut nes, None, max_instructions, &mut std::io::stdout())
+                })
+        }
+        // Prints the ROM as ca65 source, only for 16 KiB and 32 KiB PRG ROMs
+        [command, rom] if command == "disassemble" => {
+            nes::cartridge::Cartridge::from_rom(rom)
+                .map_err(|e| e.to_string())
+                .and_then(|cartridge| nes::disassembler::disassemble(&cartridge.prg_rom))
+                .map(|disassembly| print!("{}", disassembly))
+        }
+        // Compares the trace of nestest.nes against its golden log
+        [command, rom, log] if command == "nestest" => util::trace::run_nestest(rom, log),
+        // Runs Klaus Dormann's 6502 functional test, optionally with the success address of a
+        // custom build and the CPU variant to test
+        [command, bin, rest @ ..] if command == "functional" && rest.len() <= 2 => {
+            let success = match rest.first() {
+                Some(addr) => u16::from_str_radix(addr.trim_start_matches('$'), 16)
+                    .map_err(|e| format!("Invalid success address: {}", e)),
+                None => Ok(util::functional_test::FUNCTIONAL_TEST_SUCCESS),
+            };
+            let variant = match rest.get(1) {
+                Some(name) => util::functional_test::parse_variant(name),
+                None => Ok(nes::cpu::Variant::Nmos6502),
+            };
+
+            success.and_then(|success| {
+                util::functional_test::run_functional_test(bin, success, variant?)
+            })
+        }
+        // Measures the CPU speed on a tight loop, optionally over a number of instructions
+        [command, rest @ ..] if command == "bench" && rest.len() <= 1 => {
+            match rest.first().map(|count| count.parse()) {
+                Some(Ok(count)) => util::benchmark::run_benchmark(count),
+                Some(Err(e)) => Err(format!("Invalid instruction count: {}", e)),
+                None => util::benchmark::run_benchmark(util::benchmark::BENCHMARK_INSTRUCTIONS),
+            }
+        }
+        // Runs a ROM for a number of frames then saves the pattern tables, nametables and sprites
+        // as PNG files, in the current directory by default
+        [command, rom, frames, rest @ ..] if command == "viewers" && rest.len() <= 1 => {
+            let dir = rest.first().map_or(".", String::as_str);
+
+            match frames.parse::<u64>() {
+                Ok(frames) => nes::Nes::from_rom(rom)
+                    .map_err(|e| e.to_string())
+                    .and_then(|mut nes| {
+                        nes.reset();
+
+                        for _ in 0..frames {
+                            nes.run_frame().map_err(|jam| jam.to_string())?;
+                        }
+
+                        let palette = nes::palette::Palette::default();
+                        util::viewers::save_viewers(&nes, &palette, dir)
+                    }),
+                Err(e) => Err(format!("Invalid frame count: {}", e)),
+            }
+        }
+        // Runs a ROM for a number of frames then saves the last one as a PNG file, optionally
+        // scaled and without the overscan
+        [command, rom, frames, png, rest @ ..] if command == "screenshot" && rest.len() <= 2 => {
+            let scale = match rest.first() {
+                Some(scale) => match scale.parse() {
+                    Ok(0) => Err("Invalid scale: 0".to_string()),
+                    Ok(scale) => Ok(scale),
+                    Err(e) => Err(format!("Invalid scale: {}", e)),
+                },
+                None => Ok(1),
+            };
+
+            scale.and_then(|scale| {
+                let options = nes::ScreenshotOptions {
+                    crop_overscan: rest.get(1).is_some_and(|crop| crop == "crop"),
+                    scale,
+                };
+
+                screenshot(rom, frames, png, &options)
+            })
+        }
+        [] => util::debug::debug_cpu(util::debug::MULTIPLY_PROGRAM),
+        _ => Err(
+            "Usage: [trace <rom> [count] | disassemble <rom> | bench [count] | nestest <rom> <log> | functional <bin> [success] [variant] | viewers <rom> <frames> [dir] | screenshot <rom> <frames> <png> [scale] [crop]]"
+                .to_string(),
+        ),
+    };
+
+    match result {
         Ok(()) => {}
         Err(e) => {
             eprintln!("Debug session failed: {}", e);
@@ -15,13 +109,51 @@ fn main() {
     }
 }
 
+// Runs a ROM for a number of frames then saves the last one, for bug reports and golden images
+fn screenshot(
+    rom: &str,
+    frames: &str,
+    png: &str,
+    options: &nes::ScreenshotOptions,
+) -> Result<(), String> {
+    let frames: u64 = frames
+        .parse()
+        .map_err(|e| format!("Invalid frame count: {}", e))?;
+
+    let mut nes = nes::Nes::from_rom(rom).map_err(|e| e.to_string())?;
+    nes.reset();
+
+    for _ in 0..frames {
+        nes.run_frame().map_err(|jam| jam.to_string())?;
+    }
+
+    nes.save_screenshot(png, &nes::palette::Palette::default(), options)
+        .map_err(|e| format!("Failed to save {}: {}", png, e))
+}
+
 #[cfg(not(feature = "debug"))]
 fn main() {
-    let path = std::env::args().nth(1).expect("No ROM path given");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // With a frame count and a PNG path, saves a screenshot instead of running forever
+    if let [rom, frames, png] = args.as_slice() {
+        if let Err(e) = screenshot(rom, frames, png, &nes::ScreenshotOptions::default()) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
+    let path = args.first().expect("No ROM path given");
     match nes::Nes::from_rom(path) {
         Ok(mut nes) => {
             nes.reset();
-            nes.run();
+
+            if let Err(jam) = nes.run() {
+                eprintln!("{}", jam);
+                std::process::exit(1);
+            }
         }
         Err(e) => {
             eprintln!("Failed to start the NES emulator: {}", e);
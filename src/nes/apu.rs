@@ -0,0 +1,134 @@
+use super::Region;
+
+pub const ADDR_APU_STATUS: u16 = 0x4015;
+pub const ADDR_FRAME_COUNTER: u16 = 0x4017;
+
+pub struct FrameCounterFlags;
+
+impl FrameCounterFlags {
+    pub const IRQ_INHIBIT: u8 = 0b0100_0000;
+    pub const FIVE_STEP: u8 = 0b1000_0000;
+}
+
+pub struct ApuStatus;
+
+impl ApuStatus {
+    pub const FRAME_INTERRUPT: u8 = 0b0100_0000;
+}
+
+// Audio processing unit. Only the frame counter is there for now, games rely on its IRQ for timing.
+pub struct Apu {
+    region: Region,
+
+    cycle: u32,        // CPU cycles since the start of the frame counter sequence
+    odd_cycle: bool,   // The APU is clocked every other CPU cycle
+    five_step: bool,   // 5-step sequence, without IRQ
+    irq_inhibit: bool, // Frame IRQ disabled
+    frame_irq: bool,   // Frame interrupt flag, asserts the IRQ line
+    reset_delay: u8,   // CPU cycles before a $4017 write restarts the sequence
+}
+
+impl Default for Apu {
+    fn default() -> Self {
+        Self::new(Region::Ntsc)
+    }
+}
+
+impl Apu {
+    pub fn new(region: Region) -> Self {
+        Self {
+            region,
+            cycle: 0,
+            odd_cycle: false,
+            five_step: false,
+            irq_inhibit: false,
+            frame_irq: false,
+            reset_delay: 0,
+        }
+    }
+
+    // CPU cycles at which the 4-step sequence raises the frame IRQ, the last one restarts it
+    fn irq_cycles(&self) -> (u32, u32) {
+        match self.region {
+            Region::Ntsc => (29828, 29830),
+            Region::Pal => (33252, 33254),
+        }
+    }
+
+    // Length of the 5-step sequence in CPU cycles
+    fn five_step_len(&self) -> u32 {
+        match self.region {
+            Region::Ntsc => 37282,
+            Region::Pal => 41566,
+        }
+    }
+
+    // Advances one CPU cycle
+    pub fn tick(&mut self) {
+        self.odd_cycle = !self.odd_cycle;
+
+        if self.reset_delay > 0 {
+            self.reset_delay -= 1;
+
+            if self.reset_delay == 0 {
+                self.cycle = 0;
+            }
+        }
+
+        self.cycle += 1;
+
+        if self.five_step {
+            if self.cycle == self.five_step_len() {
+                self.cycle = 0;
+            }
+        } else {
+            let (first, last) = self.irq_cycles();
+
+            if (first..=last).contains(&self.cycle) && !self.irq_inhibit {
+                self.frame_irq = true;
+            }
+
+            if self.cycle == last {
+                self.cycle = 0;
+            }
+        }
+    }
+
+    // Level of the frame counter IRQ
+    pub fn irq(&self) -> bool {
+        self.frame_irq
+    }
+
+    // Reading the status acknowledges the frame interrupt
+    pub fn cpu_read(&mut self, addr: u16) -> u8 {
+        let data = self.cpu_peek(addr);
+
+        if addr == ADDR_APU_STATUS {
+            self.frame_irq = false;
+        }
+
+        data
+    }
+
+    pub fn cpu_peek(&self, addr: u16) -> u8 {
+        match addr {
+            ADDR_APU_STATUS if self.frame_irq => ApuStatus::FRAME_INTERRUPT,
+            _ => 0,
+        }
+    }
+
+    pub fn cpu_write(&mut self, addr: u16, data: u8) {
+        if addr == ADDR_FRAME_COUNTER {
+            self.five_step = data & FrameCounterFlags::FIVE_STEP != 0;
+            self.irq_inhibit = data & FrameCounterFlags::IRQ_INHIBIT != 0;
+
+            if self.irq_inhibit {
+                self.frame_irq = false;
+            }
+
+            // The sequence restarts 3 cycles after the write when it lands on an APU cycle, 4
+            // otherwise
+            self.reset_delay = if self.odd_cycle { 4 } else { 3 };
+        }
+    }
+}
@@ -0,0 +1,890 @@
+use super::bus::{ADDR_IRQ_VECTOR, ADDR_NMI_VECTOR, ADDR_PRG_ROM, ADDR_RESET_VECTOR};
+use super::instructions::{AddrMode, get_instruction};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+
+// The assembled image covers $8000-$FFFF, like an NROM-256 cartridge
+pub const PRG_ROM_SIZE: usize = 0x8000;
+
+// Labels used to fill the vectors the source leaves unset, the reset vector defaults to the first
+// assembled address
+const NMI_LABEL: &str = "nmi";
+const RESET_LABEL: &str = "reset";
+const IRQ_LABEL: &str = "irq";
+
+// Source line and reason of an assembly failure
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsmError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for AsmError {}
+
+pub struct Assembly {
+    pub prg_rom: Vec<u8>,
+    pub labels: BTreeMap<String, u16>,
+}
+
+enum Operand<'a> {
+    Implied, // Also the accumulator forms, asl a
+    Immediate(&'a str),
+    Direct(&'a str),
+    IndexedX(&'a str),
+    IndexedY(&'a str),
+    Indirect(&'a str),
+    IndirectX(&'a str),
+    IndirectY(&'a str),
+}
+
+enum Statement<'a> {
+    Empty,
+    Constant(&'a str, &'a str),
+    Org(&'a str),
+    Byte(Vec<&'a str>),
+    Word(Vec<&'a str>),
+    Instruction(String, Operand<'a>, bool), // Forced absolute addressing, like ca65's a: prefix
+}
+
+struct Line<'a> {
+    number: usize,
+    label: Option<&'a str>,
+    statement: Statement<'a>,
+}
+
+// Assembles 6502 source for Nes::from_program. Supports labels ("name:"), constants
+// ("name = expr"), the .org, .byte and .word directives, the a: prefix forcing absolute addressing
+// and expressions made of numbers ($hex, %binary, decimal, 'c'), symbols, * for the current
+// address, the unary operators - ~ < (low byte) > (high byte) and the binary operators
+// * / + - << >> & ^ |. Comments start with ;.
+pub fn assemble(source: &str) -> Result<Assembly, AsmError> {
+    let lines = source
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_line(i + 1, line))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut assembler = Assembler::new();
+
+    assembler.first_pass(&lines)?;
+    assembler.second_pass(&lines)?;
+    assembler.fill_vectors();
+
+    Ok(Assembly {
+        prg_rom: assembler.prg_rom,
+        labels: assembler.symbols,
+    })
+}
+
+fn error(line: usize, message: impl Into<String>) -> AsmError {
+    AsmError {
+        line,
+        message: message.into(),
+    }
+}
+
+// Removes the comment, ignoring semicolons in string and character literals
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, ';') => return &line[..i],
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            _ => {}
+        }
+    }
+
+    line
+}
+
+// Splits directive arguments on commas outside of string and character literals
+fn split_args(args: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut quote = None;
+    let mut start = 0;
+
+    for (i, c) in args.char_indices() {
+        match (quote, c) {
+            (None, ',') => {
+                parts.push(args[start..i].trim());
+                start = i + 1;
+            }
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            _ => {}
+        }
+    }
+
+    parts.push(args[start..].trim());
+    parts
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn strip_suffix_ignore_case<'a>(text: &'a str, suffix: &str) -> Option<&'a str> {
+    let split = text.len().checked_sub(suffix.len())?;
+
+    (text.is_char_boundary(split) && text[split..].eq_ignore_ascii_case(suffix))
+        .then(|| text[..split].trim_end())
+}
+
+// Index of the parenthesis closing the one at the start of text
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn parse_operand(operand: &str) -> Operand<'_> {
+    let operand = operand.trim();
+
+    if operand.is_empty() || operand.eq_ignore_ascii_case("a") {
+        return Operand::Implied;
+    }
+
+    if let Some(value) = operand.strip_prefix('#') {
+        return Operand::Immediate(value.trim());
+    }
+
+    if operand.starts_with('(') {
+        if let Some(inner) = strip_suffix_ignore_case(operand, ",x)") {
+            return Operand::IndirectX(inner[1..].trim());
+        }
+
+        if let Some(inner) = strip_suffix_ignore_case(operand, ",y")
+            && let Some(inner) = inner.strip_suffix(')')
+            && closing_paren(operand) == Some(inner.len())
+        {
+            return Operand::IndirectY(inner[1..].trim());
+        }
+
+        // Otherwise parentheses only group the expression, the mode is decided with the opcode
+        if closing_paren(operand) == Some(operand.len() - 1) {
+            return Operand::Indirect(operand[1..operand.len() - 1].trim());
+        }
+    }
+
+    if let Some(value) = strip_suffix_ignore_case(operand, ",x") {
+        return Operand::IndexedX(value);
+    }
+
+    if let Some(value) = strip_suffix_ignore_case(operand, ",y") {
+        return Operand::IndexedY(value);
+    }
+
+    Operand::Direct(operand)
+}
+
+fn parse_line(number: usize, line: &str) -> Result<Line<'_>, AsmError> {
+    let mut rest = strip_comment(line).trim();
+    let mut label = None;
+
+    if let Some((name, statement)) = rest.split_once(':')
+        && is_identifier(name.trim())
+    {
+        label = Some(name.trim());
+        rest = statement.trim();
+    }
+
+    let statement = if rest.is_empty() {
+        Statement::Empty
+    } else if let Some((name, value)) = rest.split_once('=')
+        && is_identifier(name.trim())
+    {
+        Statement::Constant(name.trim(), value.trim())
+    } else {
+        let (word, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+        match word.to_lowercase().as_str() {
+            ".org" => Statement::Org(args.trim()),
+            ".byte" => Statement::Byte(split_args(args)),
+            ".word" => Statement::Word(split_args(args)),
+            directive if directive.starts_with('.') => {
+                return Err(error(number, format!("Unknown directive {}", word)));
+            }
+            name => {
+                let args = args.trim();
+
+                match args.get(..2) {
+                    Some(prefix) if prefix.eq_ignore_ascii_case("a:") => {
+                        Statement::Instruction(name.to_string(), parse_operand(&args[2..]), true)
+                    }
+                    _ => Statement::Instruction(name.to_string(), parse_operand(args), false),
+                }
+            }
+        }
+    };
+
+    Ok(Line {
+        number,
+        label,
+        statement,
+    })
+}
+
+struct Assembler {
+    opcodes: HashMap<(&'static str, AddrMode), u8>,
+    symbols: BTreeMap<String, u16>,
+    pc: u16,
+    prg_rom: Vec<u8>,
+    written: Vec<bool>,
+    start: Option<u16>,
+    modes: Vec<AddrMode>, // Addressing mode chosen for each instruction by the first pass
+}
+
+impl Assembler {
+    fn new() -> Self {
+        let mut opcodes = HashMap::new();
+
+        // Official opcodes win over the unofficial duplicates, like sbc #imm and the nops
+        for opcode in 0..=0xFFu8 {
+            let instruction = get_instruction(opcode);
+            let key = (instruction.name, instruction.mode);
+
+            if instruction.official || !opcodes.contains_key(&key) {
+                opcodes.insert(key, opcode);
+            }
+        }
+
+        Self {
+            opcodes,
+            symbols: BTreeMap::new(),
+            pc: ADDR_PRG_ROM as u16,
+            prg_rom: vec![0; PRG_ROM_SIZE],
+            written: vec![false; PRG_ROM_SIZE],
+            start: None,
+            modes: vec![],
+        }
+    }
+
+    fn has_mode(&self, name: &str, mode: AddrMode) -> bool {
+        self.opcodes.keys().any(|&(n, m)| n == name && m == mode)
+    }
+
+    // Picks the addressing mode of an instruction, value is the operand if already known. Zero
+    // page forms are only preferred for operands known during the first pass, so both passes agree
+    // on the instruction length, and used for the others when there is no absolute form.
+    fn choose_mode(
+        &self,
+        name: &str,
+        operand: &Operand,
+        value: Option<i64>,
+        absolute: bool,
+        line: usize,
+    ) -> Result<AddrMode, AsmError> {
+        if !self.opcodes.keys().any(|&(n, _)| n == name) {
+            return Err(error(line, format!("Unknown instruction {}", name)));
+        }
+
+        let zero_page = !absolute && value.is_some_and(|value| (0..=0xFF).contains(&value));
+
+        let (preferred, fallback) = match operand {
+            // brk is listed with its padding byte, a bare brk assembles to brk #0
+            Operand::Implied if name == "brk" => (AddrMode::Imm, None),
+            Operand::Implied => (AddrMode::Imp, None),
+            Operand::Immediate(_) => (AddrMode::Imm, None),
+            Operand::Direct(_) if self.has_mode(name, AddrMode::Rel) => (AddrMode::Rel, None),
+            Operand::Direct(_) if zero_page => (AddrMode::Zp0, Some(AddrMode::Abs)),
+            Operand::Direct(_) => (AddrMode::Abs, Some(AddrMode::Zp0)),
+            Operand::IndexedX(_) if zero_page => (AddrMode::Zpx, Some(AddrMode::Abx)),
+            Operand::IndexedX(_) => (AddrMode::Abx, Some(AddrMode::Zpx)),
+            Operand::IndexedY(_) if zero_page => (AddrMode::Zpy, Some(AddrMode::Aby)),
+            Operand::IndexedY(_) => (AddrMode::Aby, Some(AddrMode::Zpy)),
+            Operand::Indirect(_) if self.has_mode(name, AddrMode::Ind) => (AddrMode::Ind, None),
+            // Parenthesized expression
+            Operand::Indirect(_) if zero_page => (AddrMode::Zp0, Some(AddrMode::Abs)),
+            Operand::Indirect(_) => (AddrMode::Abs, Some(AddrMode::Zp0)),
+            Operand::IndirectX(_) => (AddrMode::Izx, None),
+            Operand::IndirectY(_) => (AddrMode::Izy, None),
+        };
+
+        let fallback = fallback.filter(|_| !absolute);
+
+        [Some(preferred), fallback]
+            .into_iter()
+            .flatten()
+            .find(|&mode| self.has_mode(name, mode))
+            .ok_or_else(|| error(line, format!("Invalid addressing mode for {}", name)))
+    }
+
+    fn define(&mut self, name: &str, value: i64, line: usize) -> Result<(), AsmError> {
+        let value = to_word(value, line)?;
+
+        if self.symbols.insert(name.to_string(), value).is_some() {
+            return Err(error(line, format!("Duplicate symbol {}", name)));
+        }
+
+        Ok(())
+    }
+
+    // Computes the address of every label and the length of every statement
+    fn first_pass(&mut self, lines: &[Line]) -> Result<(), AsmError> {
+        // Constants may refer to labels defined further down, they are resolved in the second pass
+        let mut deferred = vec![];
+
+        for line in lines {
+            let number = line.number;
+
+            if let Statement::Org(expr) = &line.statement {
+                let origin = self
+                    .evaluate(expr, number)?
+                    .ok_or_else(|| error(number, "The .org address must be known in advance"))?;
+                self.pc = to_word(origin, number)?;
+            }
+
+            if let Some(label) = line.label {
+                self.define(label, self.pc as i64, number)?;
+            }
+
+            match &line.statement {
+                Statement::Empty | Statement::Org(_) => {}
+                Statement::Constant(name, expr) => match self.evaluate(expr, number)? {
+                    Some(value) => self.define(name, value, number)?,
+                    None => deferred.push((*name, *expr, number)),
+                },
+                Statement::Byte(args) => {
+                    let len: usize = args.iter().map(|arg| byte_arg_len(arg)).sum();
+                    self.advance(len, number)?;
+                }
+                Statement::Word(args) => self.advance(args.len() * 2, number)?,
+                Statement::Instruction(name, operand, absolute) => {
+                    let value = match operand_expr(operand) {
+                        Some(expr) => self.evaluate(expr, number)?,
+                        None => None,
+                    };
+
+                    let mode = self.choose_mode(name, operand, value, *absolute, number)?;
+                    self.modes.push(mode);
+
+                    self.advance(1 + mode.operand_len() as usize, number)?;
+                }
+            }
+        }
+
+        for (name, expr, number) in deferred {
+            let value = self
+                .evaluate(expr, number)?
+                .ok_or_else(|| error(number, format!("Cannot resolve {}", name)))?;
+
+            self.define(name, value, number)?;
+        }
+
+        Ok(())
+    }
+
+    fn advance(&mut self, len: usize, line: usize) -> Result<(), AsmError> {
+        let next = self.pc as usize + len;
+
+        if next > 0x10000 {
+            return Err(error(line, "Program goes past $FFFF"));
+        }
+
+        self.pc = next as u16;
+
+        Ok(())
+    }
+
+    // Emits the bytes now that every symbol is known
+    fn second_pass(&mut self, lines: &[Line]) -> Result<(), AsmError> {
+        let mut modes = std::mem::take(&mut self.modes).into_iter();
+        self.pc = ADDR_PRG_ROM as u16;
+
+        for line in lines {
+            let number = line.number;
+            // Address of the statement, the value of * in its expressions
+            let pc = self.pc;
+
+            match &line.statement {
+                Statement::Empty | Statement::Constant(..) => {}
+                Statement::Org(expr) => {
+                    self.pc = to_word(self.resolve(expr, pc, number)?, number)?;
+                }
+                Statement::Byte(args) => {
+                    for arg in args {
+                        if let Some(text) = string_literal(arg) {
+                            for byte in text.bytes() {
+                                self.emit(byte, number)?;
+                            }
+                        } else {
+                            let value = self.resolve(arg, pc, number)?;
+                            self.emit(to_byte(value, number)?, number)?;
+                        }
+                    }
+                }
+                Statement::Word(args) => {
+                    for arg in args {
+                        let value = to_word(self.resolve(arg, pc, number)?, number)?;
+
+                        self.emit(value as u8, number)?;
+                        self.emit((value >> 8) as u8, number)?;
+                    }
+                }
+                Statement::Instruction(name, operand, _) => {
+                    let mode = modes.next().expect("mode chosen by the first pass");
+                    let opcode = self.opcodes[&(name.as_str(), mode)];
+                    self.emit(opcode, number)?;
+
+                    let value = match operand_expr(operand) {
+                        Some(expr) => self.resolve(expr, pc, number)?,
+                        None => 0,
+                    };
+
+                    match mode.operand_len() {
+                        0 => {}
+                        1 if mode == AddrMode::Rel => {
+                            let offset = value - (pc as i64 + 2);
+
+                            if !(-128..=127).contains(&offset) {
+                                return Err(error(number, "Branch target out of range"));
+                            }
+
+                            self.emit(offset as u8, number)?;
+                        }
+                        1 => self.emit(to_byte(value, number)?, number)?,
+                        _ => {
+                            let value = to_word(value, number)?;
+
+                            self.emit(value as u8, number)?;
+                            self.emit((value >> 8) as u8, number)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn emit(&mut self, byte: u8, line: usize) -> Result<(), AsmError> {
+        let addr = self.pc;
+
+        let offset = (addr as usize)
+            .checked_sub(ADDR_PRG_ROM)
+            .ok_or_else(|| error(line, format!("${:04X} is outside of PRG ROM", addr)))?;
+
+        if self.written[offset] {
+            return Err(error(line, format!("${:04X} is assembled twice", addr)));
+        }
+
+        self.prg_rom[offset] = byte;
+        self.written[offset] = true;
+
+        self.start.get_or_insert(addr);
+        self.pc = addr.wrapping_add(1);
+
+        Ok(())
+    }
+
+    // Vectors left unset by the source point to the nmi, reset and irq labels
+    fn fill_vectors(&mut self) {
+        let start = self.start.unwrap_or(ADDR_PRG_ROM as u16);
+
+        let vectors = [
+            (ADDR_NMI_VECTOR, self.symbols.get(NMI_LABEL).copied()),
+            (
+                ADDR_RESET_VECTOR,
+                Some(self.symbols.get(RESET_LABEL).copied().unwrap_or(start)),
+            ),
+            (ADDR_IRQ_VECTOR, self.symbols.get(IRQ_LABEL).copied()),
+        ];
+
+        for (vector, target) in vectors {
+            let offset = vector - ADDR_PRG_ROM;
+
+            if let Some(target) = target
+                && !self.written[offset]
+                && !self.written[offset + 1]
+            {
+                self.prg_rom[offset] = target as u8;
+                self.prg_rom[offset + 1] = (target >> 8) as u8;
+            }
+        }
+    }
+
+    // Evaluates an expression in the second pass, when every symbol must be defined. pc is the
+    // address of the statement, self.pc has already moved past the bytes emitted for it.
+    fn resolve(&self, expr: &str, pc: u16, line: usize) -> Result<i64, AsmError> {
+        Expression::new(expr, &self.symbols, pc, true)
+            .parse()
+            .map(|value| value.unwrap_or_default())
+            .map_err(|message| error(line, message))
+    }
+
+    // Evaluates an expression in the first pass, None if it refers to a symbol not defined yet
+    fn evaluate(&self, expr: &str, line: usize) -> Result<Option<i64>, AsmError> {
+        Expression::new(expr, &self.symbols, self.pc, false)
+            .parse()
+            .map_err(|message| error(line, message))
+    }
+}
+
+fn operand_expr<'a>(operand: &Operand<'a>) -> Option<&'a str> {
+    match *operand {
+        Operand::Implied => None,
+        Operand::Immediate(expr)
+        | Operand::Direct(expr)
+        | Operand::IndexedX(expr)
+        | Operand::IndexedY(expr)
+        | Operand::Indirect(expr)
+        | Operand::IndirectX(expr)
+        | Operand::IndirectY(expr) => Some(expr),
+    }
+}
+
+fn string_literal(arg: &str) -> Option<&str> {
+    arg.strip_prefix('"')?.strip_suffix('"')
+}
+
+fn byte_arg_len(arg: &str) -> usize {
+    string_literal(arg).map_or(1, str::len)
+}
+
+fn to_byte(value: i64, line: usize) -> Result<u8, AsmError> {
+    match value {
+        -128..=255 => Ok(value as u8),
+        _ => Err(error(line, format!("{} doesn't fit in a byte", value))),
+    }
+}
+
+fn to_word(value: i64, line: usize) -> Result<u16, AsmError> {
+    match value {
+        -32768..=65535 => Ok(value as u16),
+        _ => Err(error(line, format!("{} doesn't fit in a word", value))),
+    }
+}
+
+// Recursive descent evaluator, from the lowest to the highest precedence:
+// | then ^ then & then << >> then + - then * / then unary operators
+struct Expression<'a> {
+    text: &'a [u8],
+    pos: usize,
+    symbols: &'a BTreeMap<String, u16>,
+    pc: u16,
+    strict: bool, // Undefined symbols are errors instead of unknown values
+}
+
+type Value = Result<Option<i64>, String>;
+
+impl<'a> Expression<'a> {
+    fn new(text: &'a str, symbols: &'a BTreeMap<String, u16>, pc: u16, strict: bool) -> Self {
+        Self {
+            text: text.as_bytes(),
+            pos: 0,
+            symbols,
+            pc,
+            strict,
+        }
+    }
+
+    fn parse(mut self) -> Value {
+        let value = self.or()?;
+
+        self.skip_spaces();
+
+        if self.pos < self.text.len() {
+            return Err(format!(
+                "Unexpected {} in expression",
+                String::from_utf8_lossy(&self.text[self.pos..])
+            ));
+        }
+
+        Ok(value)
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.text.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    // Consumes op if it is next
+    fn eat(&mut self, op: &str) -> bool {
+        self.skip_spaces();
+
+        if self.text[self.pos..].starts_with(op.as_bytes()) {
+            self.pos += op.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn binary(
+        &mut self,
+        operators: &[&str],
+        operand: fn(&mut Self) -> Value,
+        apply: fn(&str, i64, i64) -> Result<i64, String>,
+    ) -> Value {
+        let mut value = operand(self)?;
+
+        'outer: loop {
+            for &op in operators {
+                if self.eat(op) {
+                    let rhs = operand(self)?;
+
+                    value = match (value, rhs) {
+                        (Some(lhs), Some(rhs)) => Some(apply(op, lhs, rhs)?),
+                        _ => None,
+                    };
+
+                    continue 'outer;
+                }
+            }
+
+            return Ok(value);
+        }
+    }
+
+    fn or(&mut self) -> Value {
+        self.binary(&["|"], Self::xor, |_, a, b| Ok(a | b))
+    }
+
+    fn xor(&mut self) -> Value {
+        self.binary(&["^"], Self::and, |_, a, b| Ok(a ^ b))
+    }
+
+    fn and(&mut self) -> Value {
+        self.binary(&["&"], Self::shift, |_, a, b| Ok(a & b))
+    }
+
+    fn shift(&mut self) -> Value {
+        self.binary(&["<<", ">>"], Self::sum, |op, a, b| match (op, b) {
+            (_, 0..64) if op == "<<" => Ok(a << b),
+            (_, 0..64) => Ok(a >> b),
+            _ => Err(format!("Invalid shift amount {}", b)),
+        })
+    }
+
+    fn sum(&mut self) -> Value {
+        self.binary(&["+", "-"], Self::product, |op, a, b| match op {
+            "+" => Ok(a.wrapping_add(b)),
+            _ => Ok(a.wrapping_sub(b)),
+        })
+    }
+
+    fn product(&mut self) -> Value {
+        self.binary(&["*", "/"], Self::unary, |op, a, b| match op {
+            "*" => Ok(a.wrapping_mul(b)),
+            _ if b == 0 => Err("Division by zero".to_string()),
+            _ => a
+                .checked_div(b)
+                .ok_or_else(|| "Division overflow".to_string()),
+        })
+    }
+
+    fn unary(&mut self) -> Value {
+        let op: fn(i64) -> i64 = if self.eat("-") {
+            |value| value.wrapping_neg()
+        } else if self.eat("~") {
+            |value| !value
+        } else if self.eat("<") {
+            |value| value & 0xFF
+        } else if self.eat(">") {
+            |value| (value >> 8) & 0xFF
+        } else {
+            return self.primary();
+        };
+
+        Ok(self.unary()?.map(op))
+    }
+
+    fn primary(&mut self) -> Value {
+        self.skip_spaces();
+
+        let start = self.pos;
+        let rest = &self.text[start..];
+
+        match rest.first() {
+            Some(b'(') => {
+                self.pos += 1;
+                let value = self.or()?;
+
+                if !self.eat(")") {
+                    return Err("Missing )".to_string());
+                }
+
+                Ok(value)
+            }
+            Some(b'*') => {
+                self.pos += 1;
+                Ok(Some(self.pc as i64))
+            }
+            Some(b'\'') => match rest {
+                [_, c, b'\'', ..] => {
+                    self.pos += 3;
+                    Ok(Some(*c as i64))
+                }
+                _ => Err("Invalid character literal".to_string()),
+            },
+            Some(b'$') => self.number(1, 16),
+            Some(b'%') => self.number(1, 2),
+            Some(c) if c.is_ascii_digit() => self.number(0, 10),
+            Some(c) if c.is_ascii_alphabetic() || *c == b'_' => {
+                while self
+                    .text
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_')
+                {
+                    self.pos += 1;
+                }
+
+                let name = String::from_utf8_lossy(&self.text[start..self.pos]);
+
+                match self.symbols.get(name.as_ref()) {
+                    Some(&value) => Ok(Some(value as i64)),
+                    None if self.strict => Err(format!("Undefined symbol {}", name)),
+                    None => Ok(None),
+                }
+            }
+            _ => Err("Missing value in expression".to_string()),
+        }
+    }
+
+    fn number(&mut self, prefix: usize, radix: u32) -> Value {
+        self.pos += prefix;
+        let start = self.pos;
+
+        while self
+            .text
+            .get(self.pos)
+            .is_some_and(|c| (*c as char).is_digit(radix))
+        {
+            self.pos += 1;
+        }
+
+        let digits = String::from_utf8_lossy(&self.text[start..self.pos]);
+
+        i64::from_str_radix(&digits, radix)
+            .map(Some)
+            .map_err(|_| format!("Invalid number {}", digits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Bytes assembled at addr
+    fn bytes_at(assembly: &Assembly, addr: usize, len: usize) -> &[u8] {
+        let offset = addr - ADDR_PRG_ROM;
+        &assembly.prg_rom[offset..offset + len]
+    }
+
+    #[test]
+    fn bare_brk_has_padding_byte() {
+        let assembly = assemble("brk\nbrk #$12").unwrap();
+        assert_eq!(bytes_at(&assembly, 0x8000, 4), [0x00, 0x00, 0x00, 0x12]);
+    }
+
+    #[test]
+    fn labels_and_branches() {
+        let source = "
+            reset:  ldx #3
+            loop:   dex
+                    bne loop
+                    jmp reset
+        ";
+        let assembly = assemble(source).unwrap();
+
+        assert_eq!(assembly.labels["reset"], 0x8000);
+        assert_eq!(assembly.labels["loop"], 0x8002);
+        assert_eq!(
+            bytes_at(&assembly, 0x8000, 8),
+            [0xA2, 0x03, 0xCA, 0xD0, 0xFD, 0x4C, 0x00, 0x80]
+        );
+        assert_eq!(bytes_at(&assembly, ADDR_RESET_VECTOR, 2), [0x00, 0x80]);
+    }
+
+    #[test]
+    fn org_and_word() {
+        let source = "
+            .org $C000
+            start:  rts
+            .org $FFFA
+            .word $1234, start, $5678
+        ";
+        let assembly = assemble(source).unwrap();
+
+        assert_eq!(bytes_at(&assembly, 0xC000, 1), [0x60]);
+        assert_eq!(
+            bytes_at(&assembly, 0xFFFA, 6),
+            [0x34, 0x12, 0x00, 0xC0, 0x78, 0x56]
+        );
+    }
+
+    #[test]
+    fn branch_out_of_range() {
+        let source = "
+            loop:   .byte 0
+            .org $8100
+                    beq loop
+        ";
+        let error = assemble(source).err().unwrap();
+
+        assert_eq!(error.line, 4);
+        assert_eq!(error.message, "Branch target out of range");
+    }
+
+    #[test]
+    fn current_address() {
+        let assembly = assemble("jmp *\nbne *\nlda #<*\n.word *, *").unwrap();
+
+        assert_eq!(
+            bytes_at(&assembly, 0x8000, 11),
+            [
+                0x4C, 0x00, 0x80, 0xD0, 0xFE, 0xA9, 0x05, 0x07, 0x80, 0x07, 0x80
+            ]
+        );
+    }
+
+    #[test]
+    fn absolute_prefix() {
+        let assembly = assemble("lda $10\nlda a:$10\nsta a:$20,x").unwrap();
+
+        assert_eq!(
+            bytes_at(&assembly, 0x8000, 8),
+            [0xA5, 0x10, 0xAD, 0x10, 0x00, 0x9D, 0x20, 0x00]
+        );
+    }
+
+    #[test]
+    fn overflowing_expressions() {
+        let assembly = assemble("lda #-(-9223372036854775807-1) & $FF").unwrap();
+        assert_eq!(bytes_at(&assembly, 0x8000, 2), [0xA9, 0x00]);
+
+        let error = assemble("lda #(-9223372036854775807-1) / -1")
+            .err()
+            .unwrap();
+        assert_eq!(error.message, "Division overflow");
+    }
+}
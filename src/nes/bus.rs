@@ -1,45 +1,133 @@
+use super::Region;
+use super::apu::{ADDR_APU_STATUS, ADDR_FRAME_COUNTER, Apu};
 use super::cartridge::Cartridge;
-use super::ppu::Ppu;
-use std::cell::RefCell;
-use std::rc::Rc;
+use super::ppu::{OAMDATA, Ppu};
 
+pub const ADDR_OAM_DMA: u16 = 0x4014;
 pub const ADDR_PRG_RAM: usize = 0x6000;
 pub const ADDR_PRG_ROM: usize = 0x8000;
+pub const ADDR_NMI_VECTOR: usize = 0xFFFA;
 pub const ADDR_RESET_VECTOR: usize = 0xFFFC;
+pub const ADDR_IRQ_VECTOR: usize = 0xFFFE;
 
+// Devices sharing the IRQ line, each one holds its own bit asserted until acknowledged
+pub struct IrqSource;
+
+impl IrqSource {
+    pub const EXTERNAL: u8 = 0b0001;
+    pub const FRAME_COUNTER: u8 = 0b0010;
+    pub const DMC: u8 = 0b0100;
+    pub const MAPPER: u8 = 0b1000;
+}
+
+// Memory as seen by the CPU
+pub trait CpuBus {
+    fn read(&mut self, addr: u16) -> u8;
+    fn write(&mut self, addr: u16, data: u8);
+
+    // Read without side effects, for debuggers and disassemblers
+    fn peek(&self, addr: u16) -> u8;
+
+    // Called once per CPU cycle, lets the bus clock the devices attached to it
+    fn tick(&mut self) {}
+
+    // Level of the NMI line, the CPU reacts to its rising edge
+    fn nmi(&self) -> bool {
+        false
+    }
+
+    // IrqSource bits currently asserting the IRQ line, the CPU reacts to any of them
+    fn irq(&self) -> u8 {
+        0
+    }
+
+    // Cycles the CPU was halted by DMA before the last read, the bus clocks them itself
+    fn dma_cycles(&mut self) -> u64 {
+        0
+    }
+}
+
+// The bus owns the devices mapped in the CPU address space. It also schedules them from the master
+// clock: every CPU cycle it runs the PPU dots that fit in it, then the APU and the mapper.
 pub struct Bus {
     ram: [u8; 64 * 1024],
-    ppu: Rc<RefCell<Ppu>>,
-    cartridge: Rc<RefCell<Cartridge>>,
+    pub ppu: Ppu,
+    pub apu: Apu,
+    pub cartridge: Cartridge,
+
+    region: Region,
+    master_clock: u64, // Master clock cycles elapsed, CPU cycles are whole multiples
+    ppu_clock: u64,    // Master clock cycle the PPU has run up to
+    cycles: u64,       // CPU cycles elapsed, DMA included
+
+    dma_page: Option<u8>, // OAM DMA requested, it halts the CPU on its next read
+    dma_cycles: u64,
 }
 
 impl Bus {
-    pub fn new(ppu: Rc<RefCell<Ppu>>, cartridge: Rc<RefCell<Cartridge>>) -> Self {
+    pub fn new(cartridge: Cartridge, region: Region) -> Self {
         Self {
             ram: [0; 64 * 1024],
-            ppu,
+            ppu: Ppu::new(region),
+            apu: Apu::new(region),
             cartridge,
+            region,
+            master_clock: 0,
+            ppu_clock: 0,
+            cycles: 0,
+            dma_page: None,
+            dma_cycles: 0,
+        }
+    }
+
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    // Advances one CPU cycle
+    fn clock(&mut self) {
+        self.master_clock += self.region.cpu_divider();
+        self.cycles += 1;
+
+        while self.ppu_clock + self.region.ppu_divider() <= self.master_clock {
+            self.ppu.tick(&mut self.cartridge);
+            self.ppu_clock += self.region.ppu_divider();
         }
+
+        self.apu.tick();
+        self.cartridge.tick();
     }
 }
 
 impl Bus {
-    // TODO: implement usage of readonly argument
-    pub fn cpu_read(&self, addr: u16, _readonly: bool) -> u8 {
+    pub fn cpu_read(&mut self, addr: u16) -> u8 {
+        if (0x2000..0x4000).contains(&addr) {
+            // PPU registers: $2000 - $3FFF (mirrored every 8 bytes), reading some of them has side
+            // effects
+            self.ppu.cpu_read(addr, &self.cartridge)
+        } else if addr == ADDR_APU_STATUS {
+            self.apu.cpu_read(addr)
+        } else {
+            self.cpu_peek(addr)
+        }
+    }
+
+    // Read without side effects, for debuggers
+    pub fn cpu_peek(&self, addr: u16) -> u8 {
         if addr < 0x2000 {
             // Internal RAM: 0x0000 - 0x1FFF (mirrored 3 times)
             let addr = addr & 0x07FF;
             self.ram[addr as usize]
         } else if addr < 0x4000 {
-            // PPU registers: $2000 - $3FFF (mirrored every 8 bytes)
-            let reg = (addr & 0x0007) as usize;
-            self.ppu.borrow_mut().registers[reg]
+            self.ppu.cpu_peek(addr, &self.cartridge)
+        } else if addr == ADDR_APU_STATUS {
+            self.apu.cpu_peek(addr)
         } else if addr < 0x4017 {
             // APU / IO: $4000 - $4017
             self.ram[addr as usize]
         } else if addr >= ADDR_PRG_RAM as u16 {
             // Cartridge PGR-RAM and PRG-ROM: 0x6000 - 0xFFFF
-            self.cartridge.borrow().cpu_read(addr as usize)
+            self.cartridge.cpu_read(addr as usize)
         } else {
             0
         }
@@ -51,28 +139,135 @@ impl Bus {
             let addr = addr & 0x07FF;
             self.ram[addr as usize] = data;
         } else if addr < 0x4000 {
-            let reg = (addr & 0x0007) as usize;
-            self.ppu.borrow_mut().registers[reg] = data;
+            // PPU registers: $2000 - $3FFF (mirrored every 8 bytes)
+            self.ppu.cpu_write(addr, data, &mut self.cartridge);
+        } else if addr == ADDR_OAM_DMA {
+            self.dma_page = Some(data);
+        } else if addr == ADDR_APU_STATUS || addr == ADDR_FRAME_COUNTER {
+            self.apu.cpu_write(addr, data);
         } else if addr >= ADDR_PRG_RAM as u16 {
             // Cartridge PGR-RAM and PRG-ROM: 0x6000 - 0xFFFF
-            self.cartridge.borrow_mut().cpu_write(addr as usize, data);
+            self.cartridge.cpu_write(addr as usize, data);
         }
     }
 
-    // TODO: implement usage of readonly argument
-    pub fn ppu_read(&self, addr: u16, _readonly: bool) -> u8 {
-        if addr < 0x2000 {
-            // Internal RAM: 0x0000 - 0x1FFF (mirrored 3 times)
-            let addr = addr & 0x07FF;
-            self.ram[addr as usize]
-        } else {
-            0
+    // Copies a page of CPU memory to OAM through OAMDATA, starting at OAMADDR. The CPU is halted
+    // for the cycle of the read it was doing, one more to align the DMA reads on even cycles when
+    // needed, then 256 read and write pairs: 513 or 514 cycles. Returns the cycles added to the
+    // read.
+    fn oam_dma(&mut self, page: u8) -> u64 {
+        let start = self.cycles;
+
+        if self.cycles % 2 == 1 {
+            self.clock();
+        }
+
+        for offset in 0..=0xFF {
+            self.clock();
+            let data = self.cpu_read((page as u16) << 8 | offset);
+
+            self.clock();
+            self.ppu.cpu_write(OAMDATA, data, &mut self.cartridge);
         }
+
+        // The halted read is done again once the CPU resumes
+        self.clock();
+
+        self.cycles - start
     }
+}
 
-    pub fn ppu_write(&mut self, addr: u16, data: u8) {
+impl CpuBus for Bus {
+    fn read(&mut self, addr: u16) -> u8 {
+        if let Some(page) = self.dma_page.take() {
+            self.dma_cycles = self.oam_dma(page);
+        }
+
+        self.cpu_read(addr)
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.cpu_write(addr, data);
+    }
+
+    fn peek(&self, addr: u16) -> u8 {
+        self.cpu_peek(addr)
+    }
+
+    fn tick(&mut self) {
+        self.clock();
+    }
+
+    fn nmi(&self) -> bool {
+        self.ppu.nmi()
+    }
+
+    fn irq(&self) -> u8 {
+        let mut irq = 0;
+
+        if self.apu.irq() {
+            irq |= IrqSource::FRAME_COUNTER;
+        }
+
+        if self.cartridge.irq() {
+            irq |= IrqSource::MAPPER;
+        }
+
+        irq
+    }
+
+    fn dma_cycles(&mut self) -> u64 {
+        std::mem::take(&mut self.dma_cycles)
+    }
+}
+
+// Whole address space backed by RAM, without the NES memory map. Runs the CPU in isolation.
+pub struct FlatBus {
+    pub ram: [u8; 64 * 1024],
+    pub nmi: bool,
+    pub irq: u8,
+}
+
+impl Default for FlatBus {
+    fn default() -> Self {
+        Self {
+            ram: [0; 64 * 1024],
+            nmi: false,
+            irq: 0,
+        }
+    }
+}
+
+impl FlatBus {
+    // Copies a memory image starting at origin, bytes past $FFFF are dropped
+    pub fn load(&mut self, origin: u16, image: &[u8]) {
+        let origin = origin as usize;
+        let len = image.len().min(self.ram.len() - origin);
+
+        self.ram[origin..origin + len].copy_from_slice(&image[..len]);
+    }
+}
+
+impl CpuBus for FlatBus {
+    fn read(&mut self, addr: u16) -> u8 {
+        self.ram[addr as usize]
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
         self.ram[addr as usize] = data;
     }
+
+    fn peek(&self, addr: u16) -> u8 {
+        self.ram[addr as usize]
+    }
+
+    fn nmi(&self) -> bool {
+        self.nmi
+    }
+
+    fn irq(&self) -> u8 {
+        self.irq
+    }
 }
 
 #[cfg(feature = "debug")]
@@ -83,7 +278,7 @@ impl Bus {
                 print!("\n{:04X}: ", addr);
             }
 
-            print!("{:02X} ", self.cpu_read(addr, true));
+            print!("{:02X} ", self.cpu_peek(addr));
         }
 
         println!();
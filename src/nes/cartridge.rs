@@ -1,4 +1,5 @@
-use super::bus::{ADDR_PRG_ROM, ADDR_RESET_VECTOR};
+use super::Region;
+use super::assembler::assemble;
 use super::mapper::{Mapper, MapperKind};
 use std::fs::File;
 use std::io::{self, Read};
@@ -6,25 +7,46 @@ use std::path::Path;
 
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub enum Mirroring {
-    Horizontal,
-    Vertical,
-    FourScreen,
+    Horizontal, // $2000 = $2400 and $2800 = $2C00, for vertical scrolling
+    Vertical,   // $2000 = $2800 and $2400 = $2C00, for horizontal scrolling
+    FourScreen, // Extra VRAM on the cartridge
+    SingleScreenLower,
+    SingleScreenUpper,
 }
 
 pub struct Cartridge {
     pub nb_prg_banks: u8,
     pub prg_rom: Vec<u8>,
     pub chr_rom: Vec<u8>,
-    pub prg_ram_size: u8,
+    pub prg_ram_size: u8, // In 8 KiB units
+    pub region: Region,
     pub state: CartridgeState,
     pub mapper: MapperKind,
 }
 
 pub struct CartridgeState {
     pub prg_ram: Vec<u8>,
+    pub chr_ram: Vec<u8>, // Used instead of CHR ROM by cartridges without one
     pub mirroring: Mirroring,
 }
 
+const CHR_RAM_SIZE: usize = 8 * 1024;
+const PRG_RAM_UNIT: usize = 8 * 1024;
+
+// NES 2.0 headers have the timing in byte 12, iNES ones in bit 0 of byte 9 though few dumps set it.
+// Dual region and Dendy dumps run as NTSC and PAL.
+fn get_region(header: &[u8]) -> Region {
+    let nes2 = header[7] & 0x0C == 0x08;
+
+    let pal = if nes2 {
+        matches!(header[12] & 0x03, 1 | 3)
+    } else {
+        header[9] & 0x01 != 0
+    };
+
+    if pal { Region::Pal } else { Region::Ntsc }
+}
+
 fn get_mirroring(flag6: u8) -> Mirroring {
     match (flag6 & 0x08 != 0, flag6 & 0x01 != 0) {
         (true, _) => Mirroring::FourScreen,
@@ -63,6 +85,7 @@ impl Cartridge {
         let mirroring = get_mirroring(flag6);
 
         let prg_ram_size = if buffer[8] == 0 { 1 } else { buffer[8] };
+        let region = get_region(&buffer[..16]);
 
         if buffer.len() < 16 + prg_size + chr_size {
             return Err(io::Error::new(
@@ -79,35 +102,30 @@ impl Cartridge {
 
         let prg_rom = buffer[prg_start..prg_start + prg_size].to_vec();
         let chr_rom = buffer[prg_start + prg_size..prg_start + prg_size + chr_size].to_vec();
+        let chr_ram = if chr_rom.is_empty() {
+            vec![0; CHR_RAM_SIZE]
+        } else {
+            vec![]
+        };
 
         Ok(Self {
             nb_prg_banks,
             prg_rom,
             chr_rom,
             prg_ram_size,
+            region,
             mapper,
             state: CartridgeState {
-                prg_ram: vec![],
+                prg_ram: vec![0; prg_ram_size as usize * PRG_RAM_UNIT],
+                chr_ram,
                 mirroring,
             },
         })
     }
 
+    // Assembles the program into a 32 KiB NROM image, see assembler::assemble for the syntax
     pub fn from_program(program: &str) -> Result<Self, String> {
-        let program_bytes: Result<Vec<u8>, _> = program
-            .split_whitespace()
-            .map(|byte| u8::from_str_radix(byte, 16))
-            .collect();
-
-        let program_bytes = program_bytes.map_err(|e| format!("Invalid hex: {}", e))?;
-
-        let mut prg_rom = vec![0u8; 0x8000];
-
-        prg_rom[0..program_bytes.len()].copy_from_slice(&program_bytes);
-
-        let reset_offset = ADDR_RESET_VECTOR - ADDR_PRG_ROM;
-        prg_rom[reset_offset] = 0x00;
-        prg_rom[reset_offset + 1] = 0x80;
+        let prg_rom = assemble(program).map_err(|e| e.to_string())?.prg_rom;
 
         let mapper = MapperKind::from_id(0).ok_or("Unsupported mapper")?;
 
@@ -116,9 +134,11 @@ impl Cartridge {
             prg_rom,
             chr_rom: vec![],
             prg_ram_size: 1,
+            region: Region::Ntsc,
             mapper,
             state: CartridgeState {
-                prg_ram: vec![],
+                prg_ram: vec![0; PRG_RAM_UNIT],
+                chr_ram: vec![0; CHR_RAM_SIZE],
                 mirroring: Mirroring::Horizontal,
             },
         })
@@ -131,4 +151,26 @@ impl Cartridge {
     pub fn cpu_write(&mut self, addr: usize, data: u8) {
         self.mapper.cpu_write(addr, data, &mut self.state);
     }
+
+    // Pattern tables, $0000-$1FFF of the PPU address space
+    pub fn ppu_read(&self, addr: usize) -> u8 {
+        self.mapper.ppu_read(addr, self)
+    }
+
+    pub fn ppu_write(&mut self, addr: usize, data: u8) {
+        self.mapper.ppu_write(addr, data, &mut self.state);
+    }
+
+    pub fn tick(&mut self) {
+        self.mapper.tick(&mut self.state);
+    }
+
+    pub fn irq(&self) -> bool {
+        self.mapper.irq()
+    }
+
+    // Mappers can switch the mirroring at runtime
+    pub fn mirroring(&self) -> Mirroring {
+        self.state.mirroring
+    }
 }
@@ -1,8 +1,20 @@
-use super::bus::{ADDR_RESET_VECTOR, Bus};
-use super::instructions::{AddrMode, Instruction, get_instruction};
+use super::bus::{ADDR_RESET_VECTOR, CpuBus};
+use super::instructions::{AddrMode, execute, execute_65c02};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Variant {
+    #[default]
+    Ricoh2A03, // NES CPU, an NMOS 6502 with decimal mode disconnected
+    Nmos6502,
+    Wdc65C02,
+}
 
 #[derive(Default)]
 pub struct Cpu {
+    variant: Variant,
+
     a: u8,
     x: u8,
     y: u8,
@@ -10,12 +22,46 @@ pub struct Cpu {
     pc: u16, // Program counter
     p: u8,   // Processor status
 
-    addr_abs: u16, // Absolute address calculated by addressing mode
-    addr_rel: u16, // Relative address offset for branch instructions
-    opcode: u8,    // Current instruction opcode
-    cycles: u8,    // Remaining clock cycles
+    mode: AddrMode,     // Addressing mode of the current instruction
+    addr_abs: u16,      // Absolute address calculated by addressing mode
+    addr_rel: u16,      // Relative address offset for branch instructions
+    page_crossed: bool, // Indexing carried into the high byte of addr_abs
+    opcode: u8,         // Current instruction opcode
+    cycles: u16,        // Cycles of the last instruction, interrupt or reset, DMA included
+    total_cycles: u64,  // Clock cycles elapsed since power up
+
+    // Interrupt lines are polled at the end of every cycle, the previous values are the ones seen
+    // on the penultimate cycle of an instruction
+    nmi_line: bool,            // Level of the NMI line on the last cycle
+    nmi_pending: bool,         // Rising edge detected on the NMI line
+    prev_nmi_pending: bool,    // nmi_pending as of the previous cycle
+    irq_pending: bool,         // IRQ line asserted while interrupts are enabled
+    prev_irq_pending: bool,    // irq_pending as of the previous cycle
+    interrupt_requested: bool, // The next step runs the interrupt sequence
+    waiting: bool,             // Stopped by wai until an interrupt line is asserted
+
+    jam: Option<Jam>, // Set when a jam opcode locked the processor
+}
+
+// Opcode and address of the jam instruction that locked the processor
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Jam {
+    pub opcode: u8,
+    pub pc: u16,
+}
+
+impl fmt::Display for Jam {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "CPU jammed at ${:04X} (opcode ${:02X})",
+            self.pc, self.opcode
+        )
+    }
 }
 
+impl Error for Jam {}
+
 pub struct StatusFlags;
 
 impl StatusFlags {
@@ -34,13 +80,32 @@ fn calc_overflow(a: u16, b: u16, result: u16) -> bool {
     !(a ^ b) & (a ^ result) & 0x0080 != 0
 }
 
+// Value OR'ed with a by the unstable xaa and lxa opcodes, it varies between chips
+const UNSTABLE_MAGIC: u8 = 0xEE;
+
 pub fn has_flag(p: u8, flag: u8) -> bool {
     p & flag != 0
 }
 
 impl Cpu {
-    fn current_instruction(&self) -> Instruction {
-        get_instruction(self.opcode)
+    pub fn new(variant: Variant) -> Self {
+        Self {
+            variant,
+            ..Default::default()
+        }
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    fn is_65c02(&self) -> bool {
+        self.variant == Variant::Wdc65C02
+    }
+
+    // The 2A03 ignores the decimal flag
+    fn decimal_mode(&self) -> bool {
+        self.variant != Variant::Ricoh2A03 && self.has_flag(StatusFlags::DECIMAL)
     }
 
     fn has_flag(&self, flag: u8) -> bool {
@@ -57,100 +122,258 @@ impl Cpu {
         self.p |= StatusFlags::UNUSED;
     }
 
-    fn fetch(&mut self, bus: &mut Bus) -> u8 {
-        if matches!(self.current_instruction().mode, AddrMode::Imp) {
-            self.a
+    // Every bus access takes exactly one cycle, the bus is ticked before the access happens and the
+    // interrupt lines are polled after it
+    fn read<B: CpuBus + ?Sized>(&mut self, bus: &mut B, addr: u16) -> u8 {
+        bus.tick();
+        self.cycles += 1;
+        self.total_cycles += 1;
+
+        let data = bus.read(addr);
+
+        // The CPU was halted while a DMA ran
+        let dma_cycles = bus.dma_cycles();
+        self.cycles += dma_cycles as u16;
+        self.total_cycles += dma_cycles;
+        self.poll_interrupts(bus);
+
+        data
+    }
+
+    fn write<B: CpuBus + ?Sized>(&mut self, bus: &mut B, addr: u16, data: u8) {
+        bus.tick();
+        self.cycles += 1;
+        self.total_cycles += 1;
+
+        bus.write(addr, data);
+        self.poll_interrupts(bus);
+    }
+
+    fn poll_interrupts<B: CpuBus + ?Sized>(&mut self, bus: &B) {
+        self.prev_nmi_pending = self.nmi_pending;
+
+        // NMI is edge sensitive, it stays pending until serviced even if the line goes low
+        let nmi_line = bus.nmi();
+        if nmi_line && !self.nmi_line {
+            self.nmi_pending = true;
+        }
+        self.nmi_line = nmi_line;
+
+        // IRQ is level sensitive, it is lost if the line goes low before being serviced
+        self.prev_irq_pending = self.irq_pending;
+        self.irq_pending = bus.irq() != 0 && !self.has_flag(StatusFlags::INTERRUPT_DISABLE);
+    }
+
+    fn push<B: CpuBus + ?Sized>(&mut self, bus: &mut B, data: u8) {
+        self.write(bus, 0x0100 + self.sp as u16, data);
+        self.sp = self.sp.wrapping_sub(1);
+    }
+
+    fn pull<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
+        self.sp = self.sp.wrapping_add(1);
+
+        self.read(bus, 0x0100 + self.sp as u16)
+    }
+
+    // Address on the bus before the carry of the indexing is added to the high byte. The 65C02
+    // reads the last operand byte again instead, which avoids spurious reads of I/O registers.
+    fn unfixed_addr(&self) -> u16 {
+        if self.is_65c02() {
+            self.pc.wrapping_sub(1)
+        } else if self.page_crossed {
+            self.addr_abs.wrapping_sub(0x0100)
+        } else {
+            self.addr_abs
+        }
+    }
+
+    // Operand of a read instruction. The indexed modes read the unfixed address first when a page
+    // is crossed, which costs one extra cycle.
+    fn fetch<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
+        if matches!(self.mode, AddrMode::Imp) {
+            return self.a;
+        }
+
+        if self.page_crossed {
+            self.read(bus, self.unfixed_addr());
+        }
+
+        self.read(bus, self.addr_abs)
+    }
+
+    // Writes can't be undone, so the indexed modes always spend a cycle reading the unfixed
+    // address before the high byte is fixed
+    fn fix_addr<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        if matches!(self.mode, AddrMode::Abx | AddrMode::Aby | AddrMode::Izy) {
+            self.read(bus, self.unfixed_addr());
+        }
+    }
+
+    // Operand of a read-modify-write instruction. The unmodified value is written back while the
+    // result is computed, so memory sees two writes. The 65C02 reads it twice instead, and only
+    // inc and dec spend the fixing cycle when no page is crossed.
+    fn fetch_modify<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
+        if matches!(self.mode, AddrMode::Imp) {
+            return self.a;
+        }
+
+        if !self.is_65c02() || self.page_crossed || matches!(self.opcode, 0xDE | 0xFE) {
+            self.fix_addr(bus);
+        }
+
+        let fetched = self.read(bus, self.addr_abs);
+
+        if self.is_65c02() {
+            self.read(bus, self.addr_abs);
+        } else {
+            self.write(bus, self.addr_abs, fetched);
+        }
+
+        fetched
+    }
+
+    // Result of a read-modify-write instruction, stored in a by the accumulator forms
+    fn write_back<B: CpuBus + ?Sized>(&mut self, bus: &mut B, result: u8) {
+        if matches!(self.mode, AddrMode::Imp) {
+            self.a = result;
         } else {
-            bus.cpu_read(self.addr_abs, false)
+            self.write(bus, self.addr_abs, result);
         }
     }
 
-    pub fn reset(&mut self, bus: &mut Bus) {
+    fn store<B: CpuBus + ?Sized>(&mut self, bus: &mut B, data: u8) {
+        self.fix_addr(bus);
+        self.write(bus, self.addr_abs, data);
+    }
+
+    pub fn jammed(&self) -> Option<Jam> {
+        self.jam
+    }
+
+    pub fn total_cycles(&self) -> u64 {
+        self.total_cycles
+    }
+
+    pub fn reset<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.cycles = 0;
+
+        // Same sequence as an interrupt, but the pushes are turned into reads
+        self.read(bus, self.pc);
+        self.read(bus, self.pc);
+
+        for _ in 0..3 {
+            self.read(bus, 0x0100 + self.sp as u16);
+            self.sp = self.sp.wrapping_sub(1);
+        }
+
         self.addr_abs = ADDR_RESET_VECTOR as u16;
 
-        let lo = bus.cpu_read(self.addr_abs, false) as u16;
-        let hi = bus.cpu_read(self.addr_abs + 1, false) as u16;
+        let lo = self.read(bus, self.addr_abs) as u16;
+        let hi = self.read(bus, self.addr_abs + 1) as u16;
         self.pc = (hi << 8) | lo;
 
         self.a = 0;
         self.x = 0;
         self.y = 0;
-        self.sp = 0xFD;
-        self.p = StatusFlags::UNUSED;
+        self.p = StatusFlags::UNUSED | StatusFlags::INTERRUPT_DISABLE;
 
         self.addr_abs = 0;
         self.addr_rel = 0;
 
-        self.cycles = 8;
-    }
+        self.nmi_pending = false;
+        self.prev_nmi_pending = false;
+        self.irq_pending = false;
+        self.prev_irq_pending = false;
+        self.interrupt_requested = false;
+        self.waiting = false;
 
-    pub fn step(&mut self, bus: &mut Bus) {
-        if self.cycles > 0 {
-            self.cycles = self.cycles.saturating_sub(1);
+        self.jam = None;
+    }
 
-            return;
+    // Executes one instruction, or the interrupt sequence if an interrupt was polled during the
+    // previous instruction, ticking the bus once per cycle. Returns the number of cycles taken,
+    // including the cycles the CPU was halted by a DMA.
+    pub fn step<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> Result<u16, Jam> {
+        if let Some(jam) = self.jam {
+            return Err(jam);
         }
 
-        self.opcode = bus.cpu_read(self.pc, false);
-        self.pc = self.pc.wrapping_add(1);
+        self.cycles = 0;
 
-        let Instruction {
-            cycles,
-            addr,
-            operate,
-            ..
-        } = self.current_instruction();
+        if self.waiting {
+            // Any asserted line wakes the CPU up, a masked IRQ resumes after the wai
+            self.read(bus, self.pc);
 
-        self.cycles = cycles;
+            if self.nmi_pending || bus.irq() != 0 {
+                self.waiting = false;
+                self.interrupt_requested = self.nmi_pending || self.irq_pending;
+            }
 
-        // Add +1 cycle penalty only if a page is crossed and the opcode is a read
-        self.cycles += (addr)(self, bus) & (operate)(self, bus);
+            return Ok(self.cycles);
+        }
 
-        self.cycles = self.cycles.saturating_sub(1);
-    }
+        if self.interrupt_requested {
+            self.interrupt_requested = false;
+            self.interrupt(bus);
 
-    // Interrupt request
-    pub fn irq(&mut self, bus: &mut Bus) {
-        if self.has_flag(StatusFlags::INTERRUPT_DISABLE) {
-            return;
+            return Ok(self.cycles);
         }
 
-        bus.cpu_write(0x0100 + self.sp as u16, (self.pc >> 8) as u8);
-        self.sp = self.sp.wrapping_sub(1);
-        bus.cpu_write(0x0100 + self.sp as u16, self.pc as u8);
-        self.sp = self.sp.wrapping_sub(1);
+        self.page_crossed = false;
 
-        bus.cpu_write(0x0100 + self.sp as u16, self.p);
-        self.sp = self.sp.wrapping_sub(1);
+        self.opcode = self.read(bus, self.pc);
+        self.pc = self.pc.wrapping_add(1);
 
-        self.set_flag(StatusFlags::BREAK, false);
-        self.set_flag(StatusFlags::INTERRUPT_DISABLE, true);
+        let opcode = self.opcode;
 
-        let lo = bus.cpu_read(0xFFFE, false) as u16;
-        let hi = bus.cpu_read(0xFFFF, false) as u16;
-        self.pc = (hi << 8) | lo;
+        match self.variant {
+            Variant::Ricoh2A03 | Variant::Nmos6502 => execute(self, bus, opcode),
+            Variant::Wdc65C02 => execute_65c02(self, bus, opcode),
+        }
+
+        // Interrupts are polled on the penultimate cycle, which is why cli, sei and plp only take
+        // effect after the next instruction
+        self.interrupt_requested = self.prev_nmi_pending || self.prev_irq_pending;
+
+        if self.interrupt_requested {
+            self.waiting = false;
+        }
 
-        self.cycles = 7;
+        self.jam.map_or(Ok(self.cycles), Err)
     }
 
-    // Non-maskable interrupt
-    pub fn nmi(&mut self, bus: &mut Bus) {
-        bus.cpu_write(0x0100 + self.sp as u16, (self.pc >> 8) as u8);
-        self.sp = self.sp.wrapping_sub(1);
-        bus.cpu_write(0x0100 + self.sp as u16, self.pc as u8);
-        self.sp = self.sp.wrapping_sub(1);
+    // Pushes the status register then loads pc from the NMI vector if an NMI is pending at that
+    // point, otherwise from the IRQ/BRK vector. An NMI can hijack an IRQ or a brk this way.
+    fn push_status_and_vector<B: CpuBus + ?Sized>(&mut self, bus: &mut B, status: u8) {
+        let vector = if self.nmi_pending {
+            self.nmi_pending = false;
+            0xFFFA
+        } else {
+            0xFFFE
+        };
 
-        bus.cpu_write(0x0100 + self.sp as u16, self.p);
-        self.sp = self.sp.wrapping_sub(1);
+        self.push(bus, status);
 
-        self.set_flag(StatusFlags::BREAK, false);
         self.set_flag(StatusFlags::INTERRUPT_DISABLE, true);
 
-        let lo = bus.cpu_read(0xFFFA, false) as u16;
-        let hi = bus.cpu_read(0xFFFB, false) as u16;
+        if self.is_65c02() {
+            self.set_flag(StatusFlags::DECIMAL, false);
+        }
+
+        let lo = self.read(bus, vector) as u16;
+        let hi = self.read(bus, vector + 1) as u16;
         self.pc = (hi << 8) | lo;
+    }
+
+    // Hardware interrupt sequence, the opcode fetch and the following read are discarded
+    fn interrupt<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.read(bus, self.pc);
+        self.read(bus, self.pc);
 
-        self.cycles = 8;
+        self.push(bus, (self.pc >> 8) as u8);
+        self.push(bus, self.pc as u8);
+
+        self.push_status_and_vector(bus, (self.p & !StatusFlags::BREAK) | StatusFlags::UNUSED);
     }
 }
 
@@ -158,145 +381,156 @@ impl Cpu {
     // Addressing modes
 
     // Implicit
-    pub fn imp(&mut self, _bus: &mut Bus) -> u8 {
-        0
+    pub fn imp<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Imp;
+
+        // The byte after the opcode is read and ignored
+        self.read(bus, self.pc);
     }
 
     // Immediate
-    pub fn imm(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn imm<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
+        self.mode = AddrMode::Imm;
         self.addr_abs = self.pc;
         self.pc = self.pc.wrapping_add(1);
-
-        0
     }
 
     // Zero page
-    pub fn zp0(&mut self, bus: &mut Bus) -> u8 {
-        self.addr_abs = bus.cpu_read(self.pc, false) as u16;
+    pub fn zp0<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Zp0;
+        self.addr_abs = self.read(bus, self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
-
-        0
     }
 
     // Zero page indexed with x
-    pub fn zpx(&mut self, bus: &mut Bus) -> u8 {
-        self.addr_abs = bus.cpu_read(self.pc, false).wrapping_add(self.x) as u16;
+    pub fn zpx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Zpx;
+        let addr = self.read(bus, self.pc);
         self.pc = self.pc.wrapping_add(1);
 
-        0
+        // The unindexed address is read while x is added
+        self.read(bus, addr as u16);
+
+        self.addr_abs = addr.wrapping_add(self.x) as u16;
     }
 
     // Zero page indexed with y
-    pub fn zpy(&mut self, bus: &mut Bus) -> u8 {
-        self.addr_abs = bus.cpu_read(self.pc, false).wrapping_add(self.y) as u16;
+    pub fn zpy<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Zpy;
+        let addr = self.read(bus, self.pc);
         self.pc = self.pc.wrapping_add(1);
 
-        0
+        // The unindexed address is read while y is added
+        self.read(bus, addr as u16);
+
+        self.addr_abs = addr.wrapping_add(self.y) as u16;
     }
 
     // Relative
-    pub fn rel(&mut self, bus: &mut Bus) -> u8 {
-        self.addr_rel = bus.cpu_read(self.pc, false) as u16;
+    pub fn rel<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Rel;
+        self.addr_rel = self.read(bus, self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
 
         if self.addr_rel & 0x0080 != 0 {
             // If bit 7 isset, fill upper byte with 1s to preserve negative value
             self.addr_rel |= 0xFF00;
         }
-
-        0
     }
 
     // Absolute
-    pub fn abs(&mut self, bus: &mut Bus) -> u8 {
-        let lo = bus.cpu_read(self.pc, false) as u16;
+    pub fn abs<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Abs;
+        let lo = self.read(bus, self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
-        let hi = bus.cpu_read(self.pc, false) as u16;
+        let hi = self.read(bus, self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
 
         self.addr_abs = (hi << 8) | lo;
-
-        0
     }
 
     // Absolute indexed with x
-    pub fn abx(&mut self, bus: &mut Bus) -> u8 {
-        let lo = bus.cpu_read(self.pc, false) as u16;
+    pub fn abx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Abx;
+        let lo = self.read(bus, self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
-        let hi = bus.cpu_read(self.pc, false) as u16;
+        let hi = self.read(bus, self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
 
         self.addr_abs = (hi << 8) | lo;
         self.addr_abs = self.addr_abs.wrapping_add(self.x as u16);
 
-        if self.addr_abs & 0xFF00 != (hi << 8) {
-            // Page boundary crossed (+1 cycle)
-            1
-        } else {
-            0
-        }
+        // Page boundary crossed, the operand helpers spend a cycle fixing the high byte
+        self.page_crossed = self.addr_abs & 0xFF00 != hi << 8;
     }
 
     // Absolute indexed with y
-    pub fn aby(&mut self, bus: &mut Bus) -> u8 {
-        let lo = bus.cpu_read(self.pc, false) as u16;
+    pub fn aby<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Aby;
+        let lo = self.read(bus, self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
-        let hi = bus.cpu_read(self.pc, false) as u16;
+        let hi = self.read(bus, self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
 
         self.addr_abs = (hi << 8) | lo;
         self.addr_abs = self.addr_abs.wrapping_add(self.y as u16);
 
-        if self.addr_abs & 0xFF00 != (hi << 8) {
-            // Page boundary crossed (+1 cycle)
-            1
-        } else {
-            0
-        }
+        // Page boundary crossed, the operand helpers spend a cycle fixing the high byte
+        self.page_crossed = self.addr_abs & 0xFF00 != hi << 8;
     }
 
     // Indirect
-    pub fn ind(&mut self, bus: &mut Bus) -> u8 {
-        let lo = bus.cpu_read(self.pc, false) as u16;
+    pub fn ind<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Ind;
+        let lo = self.read(bus, self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
-        let hi = bus.cpu_read(self.pc, false) as u16;
+        let hi = self.read(bus, self.pc) as u16;
         self.pc = self.pc.wrapping_add(1);
 
         let addr = (hi << 8) | lo;
 
-        if lo == 0x00FF {
-            // Simulates a 6502 hardware bug: addr treated as 2 separate bytes, carry is not propagated to MSB.
-            // Example: JMP ($10FF) reads LSB from $10FF and MSB from $1000 (not $1100).
-            self.addr_abs = ((bus.cpu_read(addr & 0xFF00, false) as u16) << 8)
-                | bus.cpu_read(addr, false) as u16;
+        // Simulates a 6502 hardware bug: addr treated as 2 separate bytes, carry is not propagated to MSB.
+        // Example: JMP ($10FF) reads LSB from $10FF and MSB from $1000 (not $1100).
+        // The 65C02 fixes it at the cost of one cycle.
+        let hi_addr = if self.is_65c02() {
+            self.read(bus, self.pc.wrapping_sub(1));
+            addr.wrapping_add(1)
+        } else if lo == 0x00FF {
+            addr & 0xFF00
         } else {
-            self.addr_abs = ((bus.cpu_read(addr.wrapping_add(1), false) as u16) << 8)
-                | bus.cpu_read(addr, false) as u16;
-        }
+            addr.wrapping_add(1)
+        };
 
-        0
+        let lo = self.read(bus, addr) as u16;
+        let hi = self.read(bus, hi_addr) as u16;
+
+        self.addr_abs = (hi << 8) | lo;
     }
 
     // Indirect indexed with x
-    pub fn izx(&mut self, bus: &mut Bus) -> u8 {
-        let addr = bus.cpu_read(self.pc, false);
+    pub fn izx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Izx;
+        let addr = self.read(bus, self.pc);
         self.pc = self.pc.wrapping_add(1);
 
-        let lo = bus.cpu_read(addr.wrapping_add(self.x) as u16, false) as u16;
-        let hi = bus.cpu_read(addr.wrapping_add(self.x).wrapping_add(1) as u16, false) as u16;
+        // The pointer is read while x is added
+        self.read(bus, addr as u16);
 
-        self.addr_abs = (hi << 8) | lo;
+        let ptr = addr.wrapping_add(self.x);
+        let lo = self.read(bus, ptr as u16) as u16;
+        let hi = self.read(bus, ptr.wrapping_add(1) as u16) as u16;
 
-        0
+        self.addr_abs = (hi << 8) | lo;
     }
 
     // Indirect indexed with y
-    pub fn izy(&mut self, bus: &mut Bus) -> u8 {
-        let addr = bus.cpu_read(self.pc, false);
+    pub fn izy<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Izy;
+        let addr = self.read(bus, self.pc);
         self.pc = self.pc.wrapping_add(1);
 
-        let lo = bus.cpu_read(addr as u16, false) as u16;
-        let hi = bus.cpu_read(addr.wrapping_add(1) as u16, false) as u16;
+        let lo = self.read(bus, addr as u16) as u16;
+        let hi = self.read(bus, addr.wrapping_add(1) as u16) as u16;
 
         // Comparatively to izx, izy adds the index after dereferencing the pointer.
         // This instruction is better suited to iterate through data structures that span
@@ -304,27 +538,79 @@ impl Cpu {
         self.addr_abs = (hi << 8) | lo;
         self.addr_abs = self.addr_abs.wrapping_add(self.y as u16);
 
-        if self.addr_abs & 0xFF00 != (hi << 8) {
-            // Page boundary crossed (+1 cycle)
-            1
+        // Page boundary crossed, the operand helpers spend a cycle fixing the high byte
+        self.page_crossed = self.addr_abs & 0xFF00 != hi << 8;
+    }
+
+    // Zero page indirect
+    pub fn izp<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Izp;
+        let addr = self.read(bus, self.pc);
+        self.pc = self.pc.wrapping_add(1);
+
+        let lo = self.read(bus, addr as u16) as u16;
+        let hi = self.read(bus, addr.wrapping_add(1) as u16) as u16;
+
+        self.addr_abs = (hi << 8) | lo;
+    }
+
+    // Absolute indexed with x indirect
+    pub fn iax<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Iax;
+        let lo = self.read(bus, self.pc) as u16;
+        self.pc = self.pc.wrapping_add(1);
+        let hi = self.read(bus, self.pc) as u16;
+
+        // The last operand byte is read again while x is added
+        self.read(bus, self.pc);
+        self.pc = self.pc.wrapping_add(1);
+
+        let addr = ((hi << 8) | lo).wrapping_add(self.x as u16);
+
+        let lo = self.read(bus, addr) as u16;
+        let hi = self.read(bus, addr.wrapping_add(1)) as u16;
+
+        self.addr_abs = (hi << 8) | lo;
+    }
+
+    // Zero page then relative, the offset is read by the bit branch itself
+    pub fn zpr<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.mode = AddrMode::Zpr;
+        self.addr_abs = self.read(bus, self.pc) as u16;
+        self.pc = self.pc.wrapping_add(1);
+    }
+
+    // Single cycle implicit, the 65C02 unused opcodes don't read the next byte
+    pub fn one<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
+        self.mode = AddrMode::Imp;
+    }
+
+    // Operations shared by official and unofficial opcodes
+
+    fn add_with_carry(&mut self, value: u8) {
+        if self.decimal_mode() {
+            self.add_decimal(value);
         } else {
-            0
+            self.add_binary(value);
         }
     }
 
-    // Opcodes
-
-    // Add with carry
-    pub fn adc(&mut self, bus: &mut Bus) -> u8 {
-        let fetched = self.fetch(bus);
+    fn subtract_with_borrow(&mut self, value: u8) {
+        if self.decimal_mode() {
+            self.subtract_decimal(value);
+        } else {
+            self.add_binary(!value);
+        }
+    }
 
+    fn add_binary(&mut self, value: u8) {
         let carry = if self.has_flag(StatusFlags::CARRY) {
             1
         } else {
             0
         };
         let result = (self.a as u16)
-            .wrapping_add(fetched as u16)
+            .wrapping_add(value as u16)
             .wrapping_add(carry);
 
         self.set_flag(StatusFlags::CARRY, result & 0xFF00 != 0);
@@ -332,652 +618,974 @@ impl Cpu {
         self.set_flag(StatusFlags::NEGATIVE, result & 0x0080 != 0);
         self.set_flag(
             StatusFlags::OVERFLOW,
-            calc_overflow(self.a as u16, fetched as u16, result),
+            calc_overflow(self.a as u16, value as u16, result),
         );
 
         self.a = result as u8;
+    }
+
+    // Binary coded decimal addition. The NMOS 6502 sets zero from the binary sum, negative and
+    // overflow before the high digit is adjusted. The 65C02 sets negative and zero from the result.
+    fn add_decimal(&mut self, value: u8) {
+        let a = self.a as u16;
+        let b = value as u16;
+        let carry = if self.has_flag(StatusFlags::CARRY) {
+            1
+        } else {
+            0
+        };
+
+        let binary = a.wrapping_add(b).wrapping_add(carry);
+
+        let mut lo = (a & 0x0F) + (b & 0x0F) + carry;
+        if lo >= 0x0A {
+            lo = ((lo + 0x06) & 0x0F) + 0x10;
+        }
+
+        let mut result = (a & 0xF0) + (b & 0xF0) + lo;
+
+        self.set_flag(StatusFlags::ZERO, binary & 0x00FF == 0);
+        self.set_flag(StatusFlags::NEGATIVE, result & 0x0080 != 0);
+        self.set_flag(StatusFlags::OVERFLOW, calc_overflow(a, b, result));
+
+        if result >= 0x00A0 {
+            result += 0x0060;
+        }
+
+        self.set_flag(StatusFlags::CARRY, result >= 0x0100);
+
+        self.a = result as u8;
 
-        1
+        if self.is_65c02() {
+            self.set_flag(StatusFlags::ZERO, self.a == 0);
+            self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+        }
     }
 
-    // Bitwise and
-    pub fn and(&mut self, bus: &mut Bus) -> u8 {
-        let fetched = self.fetch(bus);
+    // Binary coded decimal subtraction. The NMOS 6502 sets every flag from the binary difference,
+    // the 65C02 sets negative and zero from the result.
+    fn subtract_decimal(&mut self, value: u8) {
+        let a = self.a as i16;
+        let b = value as i16;
+        let borrow = if self.has_flag(StatusFlags::CARRY) {
+            0
+        } else {
+            1
+        };
 
-        self.a &= fetched;
+        let lo = (a & 0x0F) - (b & 0x0F) - borrow;
 
-        self.set_flag(StatusFlags::ZERO, self.a == 0);
-        self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+        let result = if self.is_65c02() {
+            let mut result = a - b - borrow;
+            if result < 0 {
+                result -= 0x60;
+            }
+            if lo < 0 {
+                result -= 0x06;
+            }
+
+            result
+        } else {
+            let lo = if lo < 0 {
+                ((lo - 0x06) & 0x0F) - 0x10
+            } else {
+                lo
+            };
+
+            let mut result = (a & 0xF0) - (b & 0xF0) + lo;
+            if result < 0 {
+                result -= 0x60;
+            }
+
+            result
+        };
+
+        self.add_binary(!value);
 
-        1
+        self.a = result as u8;
+
+        if self.is_65c02() {
+            self.set_flag(StatusFlags::ZERO, self.a == 0);
+            self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+        }
     }
 
-    // Arithmetic shift left
-    pub fn asl(&mut self, bus: &mut Bus) -> u8 {
-        let result = (self.fetch(bus) as u16) << 1;
+    fn shift_left(&mut self, value: u8) -> u8 {
+        let result = value << 1;
 
-        self.set_flag(StatusFlags::CARRY, result & 0xFF00 != 0);
+        self.set_flag(StatusFlags::CARRY, value & 0x80 != 0);
+        self.set_flag(StatusFlags::ZERO, result == 0);
+        self.set_flag(StatusFlags::NEGATIVE, result & 0x80 != 0);
+
+        result
+    }
+
+    fn shift_right(&mut self, value: u8) -> u8 {
+        let result = value >> 1;
+
+        self.set_flag(StatusFlags::CARRY, value & 0x01 != 0);
+        self.set_flag(StatusFlags::ZERO, result == 0);
+        self.set_flag(StatusFlags::NEGATIVE, result & 0x80 != 0);
+
+        result
+    }
+
+    fn rotate_left(&mut self, value: u8) -> u8 {
+        let carry = if self.has_flag(StatusFlags::CARRY) {
+            1
+        } else {
+            0
+        };
+        let result = (value << 1) | carry;
+
+        self.set_flag(StatusFlags::CARRY, value & 0x80 != 0);
+        self.set_flag(StatusFlags::ZERO, result == 0);
+        self.set_flag(StatusFlags::NEGATIVE, result & 0x80 != 0);
+
+        result
+    }
+
+    fn rotate_right(&mut self, value: u8) -> u8 {
+        let carry = if self.has_flag(StatusFlags::CARRY) {
+            1
+        } else {
+            0
+        };
+        let result = (carry << 7) | (value >> 1);
+
+        self.set_flag(StatusFlags::CARRY, value & 0x01 != 0);
+        self.set_flag(StatusFlags::ZERO, result == 0);
+        self.set_flag(StatusFlags::NEGATIVE, result & 0x80 != 0);
+
+        result
+    }
+
+    fn set_compare_flags(&mut self, result: u16) {
+        self.set_flag(StatusFlags::CARRY, result & 0xFF00 == 0);
         self.set_flag(StatusFlags::ZERO, result & 0x00FF == 0);
         self.set_flag(StatusFlags::NEGATIVE, result & 0x0080 != 0);
+    }
 
-        if self.current_instruction().mode == AddrMode::Imp {
-            self.a = result as u8;
-        } else {
-            bus.cpu_write(self.addr_abs, result as u8);
+    fn branch<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        // A taken branch that doesn't cross a page ignores an IRQ asserted during its operand
+        // fetch, the next instruction runs before the interrupt
+        if self.irq_pending && !self.prev_irq_pending {
+            self.irq_pending = false;
         }
 
-        0
-    }
+        // The next opcode is read while the offset is added to the low byte
+        self.read(bus, self.pc);
 
-    fn branch_taken(&mut self) {
-        self.cycles += 1;
         self.addr_abs = self.pc.wrapping_add(self.addr_rel);
 
         if self.addr_abs & 0xFF00 != self.pc & 0xFF00 {
-            // Page boundary crossed (+1 cycle)
-            self.cycles += 1;
+            // Page boundary crossed, the address with the unfixed high byte is read
+            self.read(bus, (self.pc & 0xFF00) | (self.addr_abs & 0x00FF));
         }
 
         self.pc = self.addr_abs;
     }
 
+    // Opcodes
+
+    // Add with carry
+    pub fn adc<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch(bus);
+
+        // The 65C02 takes one more cycle to fix the flags in decimal mode
+        if self.is_65c02() && self.decimal_mode() {
+            self.read(bus, self.addr_abs);
+        }
+
+        self.add_with_carry(fetched);
+    }
+
+    // Bitwise and
+    pub fn and<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch(bus);
+
+        self.a &= fetched;
+
+        self.set_flag(StatusFlags::ZERO, self.a == 0);
+        self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+    }
+
+    // Arithmetic shift left
+    pub fn asl<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
+
+        let result = self.shift_left(fetched);
+
+        self.write_back(bus, result);
+    }
+
     // Branch if carry clear
-    pub fn bcc(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn bcc<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         if !self.has_flag(StatusFlags::CARRY) {
-            self.branch_taken();
+            self.branch(bus);
         }
-
-        0
     }
 
     // Branch if carry set
-    pub fn bcs(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn bcs<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         if self.has_flag(StatusFlags::CARRY) {
-            self.branch_taken();
+            self.branch(bus);
         }
-
-        0
     }
 
     // Branch if equal
-    pub fn beq(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn beq<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         if self.has_flag(StatusFlags::ZERO) {
-            self.branch_taken();
+            self.branch(bus);
         }
-
-        0
     }
 
     // Bit test
-    pub fn bit(&mut self, bus: &mut Bus) -> u8 {
+    pub fn bit<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         let fetched = self.fetch(bus);
 
         let result = self.a & fetched;
 
         self.set_flag(StatusFlags::ZERO, result == 0);
-        self.set_flag(StatusFlags::OVERFLOW, result & 0x40 != 0);
-        self.set_flag(StatusFlags::NEGATIVE, result & 0x80 != 0);
 
-        0
+        // Overflow and negative are copied from the operand, not from the result. The 65C02
+        // immediate form only sets zero.
+        if !matches!(self.mode, AddrMode::Imm) {
+            self.set_flag(StatusFlags::OVERFLOW, fetched & 0x40 != 0);
+            self.set_flag(StatusFlags::NEGATIVE, fetched & 0x80 != 0);
+        }
     }
 
     // Branch if minus
-    pub fn bmi(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn bmi<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         if self.has_flag(StatusFlags::NEGATIVE) {
-            self.branch_taken();
+            self.branch(bus);
         }
-
-        0
     }
 
     // Branch if not equal
-    pub fn bne(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn bne<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         if !self.has_flag(StatusFlags::ZERO) {
-            self.branch_taken();
+            self.branch(bus);
         }
-
-        0
     }
 
     // Branch if plus
-    pub fn bpl(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn bpl<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         if !self.has_flag(StatusFlags::NEGATIVE) {
-            self.branch_taken();
+            self.branch(bus);
         }
-
-        0
     }
 
     // Break (software IRQ)
-    pub fn brk(&mut self, bus: &mut Bus) -> u8 {
-        self.pc = self.pc.wrapping_add(1);
-
-        self.set_flag(StatusFlags::INTERRUPT_DISABLE, true);
-
-        bus.cpu_write(0x0100 + self.sp as u16, (self.pc >> 8) as u8);
-        self.sp = self.sp.wrapping_sub(1);
-        bus.cpu_write(0x0100 + self.sp as u16, self.pc as u8);
-        self.sp = self.sp.wrapping_sub(1);
+    pub fn brk<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        // The padding byte after the opcode was skipped by the immediate addressing mode, it is
+        // still read
+        self.read(bus, self.addr_abs);
 
-        self.set_flag(StatusFlags::BREAK, true);
-
-        bus.cpu_write(0x0100 + self.sp as u16, self.p);
-        self.sp = self.sp.wrapping_sub(1);
+        self.push(bus, (self.pc >> 8) as u8);
+        self.push(bus, self.pc as u8);
 
-        self.set_flag(StatusFlags::BREAK, false);
+        self.push_status_and_vector(bus, self.p | StatusFlags::BREAK | StatusFlags::UNUSED);
 
-        let lo = bus.cpu_read(0xFFFE, false) as u16;
-        let hi = bus.cpu_read(0xFFFF, false) as u16;
-        self.pc = (hi << 8) | lo;
-
-        0
+        // An NMI detected during the vector fetch waits for the first instruction of the handler
+        self.prev_nmi_pending = false;
     }
 
     // Branch if overflow clear
-    pub fn bvc(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn bvc<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         if !self.has_flag(StatusFlags::OVERFLOW) {
-            self.branch_taken();
+            self.branch(bus);
         }
-
-        0
     }
 
     // Branch if overflow set
-    pub fn bvs(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn bvs<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         if self.has_flag(StatusFlags::OVERFLOW) {
-            self.branch_taken();
+            self.branch(bus);
         }
-
-        0
     }
 
     // Clear carry
-    pub fn clc(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn clc<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.set_flag(StatusFlags::CARRY, false);
-
-        0
     }
 
     // Clear decimal
-    pub fn cld(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn cld<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.set_flag(StatusFlags::DECIMAL, false);
-
-        0
     }
 
     // Clear interrupt disable
-    pub fn cli(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn cli<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.set_flag(StatusFlags::INTERRUPT_DISABLE, false);
-
-        0
     }
 
     // Clear overflow
-    pub fn clv(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn clv<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.set_flag(StatusFlags::OVERFLOW, false);
-
-        0
-    }
-
-    fn set_compare_flags(&mut self, result: u16) {
-        self.set_flag(StatusFlags::CARRY, result & 0xFF00 == 0);
-        self.set_flag(StatusFlags::ZERO, result & 0x00FF == 0);
-        self.set_flag(StatusFlags::NEGATIVE, result & 0x0080 != 0);
     }
 
     // Compare a
-    pub fn cmp(&mut self, bus: &mut Bus) -> u8 {
+    pub fn cmp<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         let fetched = self.fetch(bus);
 
         self.set_compare_flags((self.a as u16).wrapping_sub(fetched as u16));
-
-        1
     }
 
     // Compare x
-    pub fn cpx(&mut self, bus: &mut Bus) -> u8 {
+    pub fn cpx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         let fetched = self.fetch(bus);
 
         self.set_compare_flags((self.x as u16).wrapping_sub(fetched as u16));
-
-        0
     }
 
     // Compare y
-    pub fn cpy(&mut self, bus: &mut Bus) -> u8 {
+    pub fn cpy<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         let fetched = self.fetch(bus);
 
         self.set_compare_flags((self.y as u16).wrapping_sub(fetched as u16));
-
-        0
     }
 
     // Decrement memory
-    pub fn dec(&mut self, bus: &mut Bus) -> u8 {
-        let result = self.fetch(bus).wrapping_sub(1);
+    pub fn dec<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let result = self.fetch_modify(bus).wrapping_sub(1);
 
-        bus.cpu_write(self.addr_abs, result);
+        self.write_back(bus, result);
 
         self.set_flag(StatusFlags::ZERO, result == 0);
         self.set_flag(StatusFlags::NEGATIVE, result & 0x80 != 0);
-
-        0
     }
 
     // Decrement x
-    pub fn dex(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn dex<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.x = self.x.wrapping_sub(1);
 
         self.set_flag(StatusFlags::ZERO, self.x == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.x & 0x80 != 0);
-
-        0
     }
 
     // Decrement y
-    pub fn dey(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn dey<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.y = self.y.wrapping_sub(1);
 
         self.set_flag(StatusFlags::ZERO, self.y == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.y & 0x80 != 0);
-
-        0
     }
 
     // Bitwise exclusive or
-    pub fn eor(&mut self, bus: &mut Bus) -> u8 {
+    pub fn eor<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         let fetched = self.fetch(bus);
 
         self.a ^= fetched;
 
         self.set_flag(StatusFlags::ZERO, self.a == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
-
-        0
     }
 
     // Increment memory
-    pub fn inc(&mut self, bus: &mut Bus) -> u8 {
-        let result = self.fetch(bus).wrapping_add(1);
+    pub fn inc<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let result = self.fetch_modify(bus).wrapping_add(1);
 
-        bus.cpu_write(self.addr_abs, result);
+        self.write_back(bus, result);
 
         self.set_flag(StatusFlags::ZERO, result == 0);
         self.set_flag(StatusFlags::NEGATIVE, result & 0x80 != 0);
-
-        0
     }
 
     // Increment x
-    pub fn inx(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn inx<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.x = self.x.wrapping_add(1);
 
         self.set_flag(StatusFlags::ZERO, self.x == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.x & 0x80 != 0);
-
-        0
     }
 
     // Increment y
-    pub fn iny(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn iny<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.y = self.y.wrapping_add(1);
 
         self.set_flag(StatusFlags::ZERO, self.y == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.y & 0x80 != 0);
-
-        0
     }
 
     // Jump
-    pub fn jmp(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn jmp<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.pc = self.addr_abs;
-
-        0
     }
 
     // Jump to subroutine
-    pub fn jsr(&mut self, bus: &mut Bus) -> u8 {
-        self.pc = self.pc.wrapping_sub(1);
+    pub fn jsr<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        // Uses the immediate addressing mode: the high byte of the target is only read after the
+        // return address, which points to it, has been pushed
+        let lo = self.read(bus, self.addr_abs) as u16;
 
-        bus.cpu_write(0x0100 + self.sp as u16, (self.pc >> 8) as u8);
-        self.sp = self.sp.wrapping_sub(1);
-        bus.cpu_write(0x0100 + self.sp as u16, self.pc as u8);
-        self.sp = self.sp.wrapping_sub(1);
+        self.read(bus, 0x0100 + self.sp as u16);
 
-        self.pc = self.addr_abs;
+        self.push(bus, (self.pc >> 8) as u8);
+        self.push(bus, self.pc as u8);
+
+        let hi = self.read(bus, self.pc) as u16;
 
-        0
+        self.pc = (hi << 8) | lo;
     }
 
     // Load a
-    pub fn lda(&mut self, bus: &mut Bus) -> u8 {
+    pub fn lda<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         let fetched = self.fetch(bus);
 
         self.a = fetched;
 
         self.set_flag(StatusFlags::ZERO, self.a == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
-
-        0
     }
 
     // Load x
-    pub fn ldx(&mut self, bus: &mut Bus) -> u8 {
+    pub fn ldx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         let fetched = self.fetch(bus);
 
         self.x = fetched;
 
         self.set_flag(StatusFlags::ZERO, self.x == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.x & 0x80 != 0);
-
-        0
     }
 
     // Load y
-    pub fn ldy(&mut self, bus: &mut Bus) -> u8 {
+    pub fn ldy<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         let fetched = self.fetch(bus);
 
         self.y = fetched;
 
         self.set_flag(StatusFlags::ZERO, self.y == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.y & 0x80 != 0);
-
-        0
     }
 
     // Logical shift right
-    pub fn lsr(&mut self, bus: &mut Bus) -> u8 {
-        let fetched = self.fetch(bus);
+    pub fn lsr<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
 
-        let result = fetched >> 1;
+        let result = self.shift_right(fetched);
 
-        self.set_flag(StatusFlags::CARRY, fetched & 0x01 != 0);
-        self.set_flag(StatusFlags::ZERO, result == 0);
-        self.set_flag(StatusFlags::NEGATIVE, result & 0x80 != 0);
-
-        if self.current_instruction().mode == AddrMode::Imp {
-            self.a = result;
-        } else {
-            bus.cpu_write(self.addr_abs, result);
-        }
-
-        0
+        self.write_back(bus, result);
     }
 
     // No operation
-    pub fn nop(&mut self, _bus: &mut Bus) -> u8 {
-        0
+    pub fn nop<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        // The unofficial forms with an operand still read it
+        self.fetch(bus);
     }
 
     // Bitwise or
-    pub fn ora(&mut self, bus: &mut Bus) -> u8 {
+    pub fn ora<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         let fetched = self.fetch(bus);
 
         self.a |= fetched;
 
         self.set_flag(StatusFlags::ZERO, self.a == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
-
-        1
     }
 
     // Push a
-    pub fn pha(&mut self, bus: &mut Bus) -> u8 {
-        bus.cpu_write(0x0100 + self.sp as u16, self.a);
-        self.sp = self.sp.wrapping_sub(1);
-
-        0
+    pub fn pha<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.push(bus, self.a);
     }
 
     // Push processor status
-    pub fn php(&mut self, bus: &mut Bus) -> u8 {
-        bus.cpu_write(
-            0x0100 + self.sp as u16,
-            self.p | StatusFlags::BREAK | StatusFlags::UNUSED,
-        );
-        self.sp = self.sp.wrapping_sub(1);
-
-        self.set_flag(StatusFlags::BREAK, false);
-
-        0
+    pub fn php<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.push(bus, self.p | StatusFlags::BREAK | StatusFlags::UNUSED);
     }
 
     // Pull a
-    pub fn pla(&mut self, bus: &mut Bus) -> u8 {
-        self.sp = self.sp.wrapping_add(1);
+    pub fn pla<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        // The top of the stack is read while the stack pointer is incremented
+        self.read(bus, 0x0100 + self.sp as u16);
 
-        self.a = bus.cpu_read(0x0100 + self.sp as u16, false);
+        self.a = self.pull(bus);
 
         self.set_flag(StatusFlags::ZERO, self.a == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
-
-        0
     }
 
     // Pull processor status
-    pub fn plp(&mut self, bus: &mut Bus) -> u8 {
-        self.sp = self.sp.wrapping_add(1);
-
-        let temp = bus.cpu_read(0x0100 + self.sp as u16, false);
+    pub fn plp<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        // The top of the stack is read while the stack pointer is incremented
+        self.read(bus, 0x0100 + self.sp as u16);
 
-        self.p |= temp & 0b1100_1111;
+        let temp = self.pull(bus);
 
-        0
+        // Break and unused are not real flags, they are ignored when pulled
+        self.p = (temp & 0b1100_1111) | StatusFlags::UNUSED;
     }
 
     // Rotate left
-    pub fn rol(&mut self, bus: &mut Bus) -> u8 {
-        let fetched = self.fetch(bus);
-
-        let carry = if self.has_flag(StatusFlags::CARRY) {
-            1
-        } else {
-            0
-        };
-        let result = (fetched << 1) | carry;
+    pub fn rol<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
 
-        self.set_flag(StatusFlags::CARRY, fetched & 0x80 != 0);
-        self.set_flag(StatusFlags::ZERO, result == 0);
-        self.set_flag(StatusFlags::NEGATIVE, result & 0x80 != 0);
+        let result = self.rotate_left(fetched);
 
-        if self.current_instruction().mode == AddrMode::Imp {
-            self.a = result;
-        } else {
-            bus.cpu_write(self.addr_abs, result);
-        }
-
-        0
+        self.write_back(bus, result);
     }
 
     // Rotate right
-    pub fn ror(&mut self, bus: &mut Bus) -> u8 {
-        let fetched = self.fetch(bus);
-
-        let carry = if self.has_flag(StatusFlags::CARRY) {
-            1
-        } else {
-            0
-        };
-        let result = (carry << 7) | (fetched >> 1);
+    pub fn ror<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
 
-        self.set_flag(StatusFlags::CARRY, fetched & 0x01 != 0);
-        self.set_flag(StatusFlags::ZERO, result == 0);
-        self.set_flag(StatusFlags::NEGATIVE, result & 0x80 != 0);
+        let result = self.rotate_right(fetched);
 
-        if self.current_instruction().mode == AddrMode::Imp {
-            self.a = result;
-        } else {
-            bus.cpu_write(self.addr_abs, result);
-        }
-
-        0
+        self.write_back(bus, result);
     }
 
     // Return from interrupt
-    pub fn rti(&mut self, bus: &mut Bus) -> u8 {
-        self.sp = self.sp.wrapping_add(1);
+    pub fn rti<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        // The top of the stack is read while the stack pointer is incremented
+        self.read(bus, 0x0100 + self.sp as u16);
 
-        let temp = bus.cpu_read(0x0100 + self.sp as u16, false);
+        let temp = self.pull(bus);
 
-        self.p |= temp & 0b1100_1111;
+        // Break and unused are not real flags, they are ignored when pulled
+        self.p = (temp & 0b1100_1111) | StatusFlags::UNUSED;
 
-        self.sp = self.sp.wrapping_add(1);
-        let lo = bus.cpu_read(0x0100 + self.sp as u16, false) as u16;
-        self.sp = self.sp.wrapping_add(1);
-        let hi = bus.cpu_read(0x0100 + self.sp as u16, false) as u16;
+        let lo = self.pull(bus) as u16;
+        let hi = self.pull(bus) as u16;
 
         self.pc = (hi << 8) | lo;
-
-        0
     }
 
     // Return from subroutine
-    pub fn rts(&mut self, bus: &mut Bus) -> u8 {
-        self.sp = self.sp.wrapping_add(1);
-        let lo = bus.cpu_read(0x0100 + self.sp as u16, false) as u16;
-        self.sp = self.sp.wrapping_add(1);
-        let hi = bus.cpu_read(0x0100 + self.sp as u16, false) as u16;
+    pub fn rts<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        // The top of the stack is read while the stack pointer is incremented
+        self.read(bus, 0x0100 + self.sp as u16);
+
+        let lo = self.pull(bus) as u16;
+        let hi = self.pull(bus) as u16;
 
         self.pc = (hi << 8) | lo;
 
+        // The pulled address points to the last byte of the jsr, it is read while incremented
+        self.read(bus, self.pc);
         self.pc = self.pc.wrapping_add(1);
-
-        0
     }
 
     // Subtract with carry
-    pub fn sbc(&mut self, bus: &mut Bus) -> u8 {
+    pub fn sbc<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         let fetched = self.fetch(bus);
-        let inverted = !fetched;
-
-        let carry = if self.has_flag(StatusFlags::CARRY) {
-            1
-        } else {
-            0
-        };
-        let result = (self.a as u16)
-            .wrapping_add(inverted as u16)
-            .wrapping_add(carry);
 
-        self.set_flag(StatusFlags::CARRY, result & 0xFF00 != 0);
-        self.set_flag(StatusFlags::ZERO, result & 0x00FF == 0);
-        self.set_flag(StatusFlags::NEGATIVE, result & 0x0080 != 0);
-        self.set_flag(
-            StatusFlags::OVERFLOW,
-            calc_overflow(self.a as u16, inverted as u16, result),
-        );
-
-        self.a = result as u8;
+        // The 65C02 takes one more cycle to fix the flags in decimal mode
+        if self.is_65c02() && self.decimal_mode() {
+            self.read(bus, self.addr_abs);
+        }
 
-        1
+        self.subtract_with_borrow(fetched);
     }
 
     // Set carry
-    pub fn sec(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn sec<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.set_flag(StatusFlags::CARRY, true);
-
-        0
     }
 
     // Set decimal
-    pub fn sed(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn sed<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.set_flag(StatusFlags::DECIMAL, true);
-
-        0
     }
 
     // Set interrupt disable
-    pub fn sei(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn sei<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.set_flag(StatusFlags::INTERRUPT_DISABLE, true);
-
-        0
     }
 
     // Store a
-    pub fn sta(&mut self, bus: &mut Bus) -> u8 {
-        bus.cpu_write(self.addr_abs, self.a);
-
-        0
+    pub fn sta<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.store(bus, self.a);
     }
 
     // Store x
-    pub fn stx(&mut self, bus: &mut Bus) -> u8 {
-        bus.cpu_write(self.addr_abs, self.x);
-
-        0
+    pub fn stx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.store(bus, self.x);
     }
 
     // Store y
-    pub fn sty(&mut self, bus: &mut Bus) -> u8 {
-        bus.cpu_write(self.addr_abs, self.y);
-
-        0
+    pub fn sty<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.store(bus, self.y);
     }
 
     // Transfer a to x
-    pub fn tax(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn tax<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.x = self.a;
 
         self.set_flag(StatusFlags::ZERO, self.x == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.x & 0x80 != 0);
-
-        0
     }
 
     // Transfer a to y
-    pub fn tay(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn tay<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.y = self.a;
 
         self.set_flag(StatusFlags::ZERO, self.y == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.y & 0x80 != 0);
-
-        0
     }
 
     // Transfer stack pointer to x
-    pub fn tsx(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn tsx<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.x = self.sp;
 
         self.set_flag(StatusFlags::ZERO, self.x == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.x & 0x80 != 0);
-
-        0
     }
 
     // Transfer x to a
-    pub fn txa(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn txa<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.a = self.x;
 
         self.set_flag(StatusFlags::ZERO, self.a == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
-
-        0
     }
 
     // Transfer x to stack pointer
-    pub fn txs(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn txs<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.sp = self.x;
-
-        0
     }
 
     // Transfer y to a
-    pub fn tya(&mut self, _bus: &mut Bus) -> u8 {
+    pub fn tya<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
         self.a = self.y;
 
         self.set_flag(StatusFlags::ZERO, self.a == 0);
         self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+    }
+
+    // Unofficial opcodes
+
+    // Store a & x & (high byte of address + 1), unstable
+    pub fn ahx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.store_unstable(bus, self.a & self.x, self.y);
+    }
+
+    // Bitwise and then logical shift right of a
+    pub fn alr<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch(bus);
+
+        self.a = self.shift_right(self.a & fetched);
+    }
+
+    // Bitwise and, then copy bit 7 to carry
+    pub fn anc<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch(bus);
+
+        self.a &= fetched;
+
+        self.set_flag(StatusFlags::CARRY, self.a & 0x80 != 0);
+        self.set_flag(StatusFlags::ZERO, self.a == 0);
+        self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+    }
+
+    // Bitwise and then rotate right of a
+    pub fn arr<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch(bus);
+
+        self.a = self.rotate_right(self.a & fetched);
+
+        // Carry and overflow come from bits 6 and 5 of the result, as if an addition took place
+        self.set_flag(StatusFlags::CARRY, self.a & 0x40 != 0);
+        self.set_flag(
+            StatusFlags::OVERFLOW,
+            ((self.a >> 6) ^ (self.a >> 5)) & 0x01 != 0,
+        );
+    }
+
+    // Subtract from a & x into x, without borrow
+    pub fn axs<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch(bus);
+
+        let result = ((self.a & self.x) as u16).wrapping_sub(fetched as u16);
+
+        self.set_compare_flags(result);
+
+        self.x = result as u8;
+    }
+
+    // Decrement memory then compare a
+    pub fn dcp<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let result = self.fetch_modify(bus).wrapping_sub(1);
+
+        self.write(bus, self.addr_abs, result);
+
+        self.set_compare_flags((self.a as u16).wrapping_sub(result as u16));
+    }
+
+    // Increment memory then subtract with carry
+    pub fn isc<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let result = self.fetch_modify(bus).wrapping_add(1);
+
+        self.write(bus, self.addr_abs, result);
+
+        self.subtract_with_borrow(result);
+    }
+
+    // Halt the processor, only a reset recovers from it
+    pub fn jam<B: CpuBus + ?Sized>(&mut self, _bus: &mut B) {
+        self.jam = Some(Jam {
+            opcode: self.opcode,
+            pc: self.pc.wrapping_sub(1),
+        });
+    }
+
+    // Load a & memory & stack pointer into a, x and the stack pointer
+    pub fn las<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch(bus);
+
+        self.sp &= fetched;
+        self.a = self.sp;
+        self.x = self.sp;
+
+        self.set_flag(StatusFlags::ZERO, self.a == 0);
+        self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+    }
+
+    // Load a and x
+    pub fn lax<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch(bus);
+
+        self.a = fetched;
+        self.x = fetched;
+
+        self.set_flag(StatusFlags::ZERO, self.a == 0);
+        self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+    }
+
+    // Load a and x from immediate, unstable
+    pub fn lxa<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch(bus);
+
+        self.a = (self.a | UNSTABLE_MAGIC) & fetched;
+        self.x = self.a;
+
+        self.set_flag(StatusFlags::ZERO, self.a == 0);
+        self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+    }
+
+    // Rotate memory left then bitwise and
+    pub fn rla<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
+
+        let result = self.rotate_left(fetched);
+        self.write(bus, self.addr_abs, result);
+
+        self.a &= result;
+
+        self.set_flag(StatusFlags::ZERO, self.a == 0);
+        self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+    }
+
+    // Rotate memory right then add with carry
+    pub fn rra<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
+
+        let result = self.rotate_right(fetched);
+        self.write(bus, self.addr_abs, result);
+
+        self.add_with_carry(result);
+    }
+
+    // Store a & x
+    pub fn sax<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.store(bus, self.a & self.x);
+    }
 
-        0
+    // Store x & (high byte of address + 1), unstable
+    pub fn shx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.store_unstable(bus, self.x, self.y);
     }
 
-    // Invalid operations
-    pub fn xxx(&mut self, _bus: &mut Bus) -> u8 {
-        0
+    // Store y & (high byte of address + 1), unstable
+    pub fn shy<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.store_unstable(bus, self.y, self.x);
+    }
+
+    // Arithmetic shift memory left then bitwise or
+    pub fn slo<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
+
+        let result = self.shift_left(fetched);
+        self.write(bus, self.addr_abs, result);
+
+        self.a |= result;
+
+        self.set_flag(StatusFlags::ZERO, self.a == 0);
+        self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+    }
+
+    // Logical shift memory right then bitwise exclusive or
+    pub fn sre<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
+
+        let result = self.shift_right(fetched);
+        self.write(bus, self.addr_abs, result);
+
+        self.a ^= result;
+
+        self.set_flag(StatusFlags::ZERO, self.a == 0);
+        self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+    }
+
+    // Transfer a & x to stack pointer, then store like ahx, unstable
+    pub fn tas<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.sp = self.a & self.x;
+
+        self.store_unstable(bus, self.sp, self.y);
+    }
+
+    // Transfer x to a then bitwise and, unstable
+    pub fn xaa<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch(bus);
+
+        self.a = (self.a | UNSTABLE_MAGIC) & self.x & fetched;
+
+        self.set_flag(StatusFlags::ZERO, self.a == 0);
+        self.set_flag(StatusFlags::NEGATIVE, self.a & 0x80 != 0);
+    }
+
+    // The unstable stores (ahx, shx, shy, tas) AND the stored value with the high byte of the base
+    // address + 1. When the indexing crosses a page, that value also replaces the high byte of the
+    // effective address.
+    fn store_unstable<B: CpuBus + ?Sized>(&mut self, bus: &mut B, value: u8, index: u8) {
+        self.fix_addr(bus);
+
+        let base = self.addr_abs.wrapping_sub(index as u16);
+        let value = value & ((base >> 8) as u8).wrapping_add(1);
+
+        if self.page_crossed {
+            self.addr_abs = ((value as u16) << 8) | (self.addr_abs & 0x00FF);
+        }
+
+        self.write(bus, self.addr_abs, value);
+    }
+}
+
+impl Cpu {
+    // 65C02 opcodes
+
+    // Bit number encoded in the high nibble of the rmb, smb, bbr and bbs opcodes
+    fn opcode_bit(&self) -> u8 {
+        1 << ((self.opcode >> 4) & 0x07)
+    }
+
+    // Branch on bit reset
+    pub fn bbr<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.branch_on_bit(bus, false);
+    }
+
+    // Branch on bit set
+    pub fn bbs<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.branch_on_bit(bus, true);
+    }
+
+    fn branch_on_bit<B: CpuBus + ?Sized>(&mut self, bus: &mut B, set: bool) {
+        let fetched = self.read(bus, self.addr_abs);
+        self.read(bus, self.addr_abs);
+
+        self.rel(bus);
+
+        if (fetched & self.opcode_bit() != 0) == set {
+            self.branch(bus);
+        }
+    }
+
+    // Branch always
+    pub fn bra<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.branch(bus);
+    }
+
+    // No operation, 8 cycles long
+    pub fn nop_long<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        for _ in 0..5 {
+            self.read(bus, 0xFF00 | (self.addr_abs & 0x00FF));
+        }
+    }
+
+    // Push x
+    pub fn phx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.push(bus, self.x);
+    }
+
+    // Push y
+    pub fn phy<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.push(bus, self.y);
+    }
+
+    // Pull x
+    pub fn plx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        // The top of the stack is read while the stack pointer is incremented
+        self.read(bus, 0x0100 + self.sp as u16);
+
+        self.x = self.pull(bus);
+
+        self.set_flag(StatusFlags::ZERO, self.x == 0);
+        self.set_flag(StatusFlags::NEGATIVE, self.x & 0x80 != 0);
+    }
+
+    // Pull y
+    pub fn ply<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        // The top of the stack is read while the stack pointer is incremented
+        self.read(bus, 0x0100 + self.sp as u16);
+
+        self.y = self.pull(bus);
+
+        self.set_flag(StatusFlags::ZERO, self.y == 0);
+        self.set_flag(StatusFlags::NEGATIVE, self.y & 0x80 != 0);
+    }
+
+    // Reset memory bit
+    pub fn rmb<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
+
+        self.write_back(bus, fetched & !self.opcode_bit());
+    }
+
+    // Set memory bit
+    pub fn smb<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
+
+        self.write_back(bus, fetched | self.opcode_bit());
+    }
+
+    // Stop the processor, only a reset recovers from it
+    pub fn stp<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.read(bus, self.pc);
+
+        self.jam(bus);
+    }
+
+    // Store zero
+    pub fn stz<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.store(bus, 0);
+    }
+
+    // Test and reset memory bits with a
+    pub fn trb<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
+
+        self.set_flag(StatusFlags::ZERO, self.a & fetched == 0);
+
+        self.write_back(bus, fetched & !self.a);
+    }
+
+    // Test and set memory bits with a
+    pub fn tsb<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        let fetched = self.fetch_modify(bus);
+
+        self.set_flag(StatusFlags::ZERO, self.a & fetched == 0);
+
+        self.write_back(bus, fetched | self.a);
+    }
+
+    // Wait for interrupt
+    pub fn wai<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
+        self.read(bus, self.pc);
+
+        self.waiting = true;
     }
 }
 
-#[cfg(feature = "debug")]
 pub struct CpuState {
     pub a: u8,
     pub x: u8,
@@ -986,10 +1594,9 @@ pub struct CpuState {
     pub pc: u16,
     pub p: u8,
     pub opcode: u8,
-    pub cycles: u8,
+    pub cycles: u16,
 }
 
-#[cfg(feature = "debug")]
 impl Cpu {
     #[rustfmt::skip]
     pub fn get_state(&self) -> CpuState {
@@ -997,11 +1604,473 @@ impl Cpu {
         CpuState {a, x, y, sp, pc, p, opcode, cycles}
     }
 
-    pub fn step_to_next_instruction(&mut self, bus: &mut Bus) {
-        self.step(bus);
+    // Loads the registers from state, opcode and cycles are left untouched
+    pub fn set_state(&mut self, state: &CpuState) {
+        self.a = state.a;
+        self.x = state.x;
+        self.y = state.y;
+        self.sp = state.sp;
+        self.pc = state.pc;
+        self.p = state.p;
+    }
+
+    pub fn set_pc(&mut self, pc: u16) {
+        self.pc = pc;
+    }
+}
+
+// The run functions stop between instructions and return the number of cycles elapsed
+impl Cpu {
+    // Runs until at least cycles have elapsed, the last instruction may overshoot
+    pub fn run_cycles<B: CpuBus + ?Sized>(&mut self, bus: &mut B, cycles: u64) -> Result<u64, Jam> {
+        let start = self.total_cycles;
+
+        while self.total_cycles - start < cycles {
+            self.step(bus)?;
+        }
+
+        Ok(self.total_cycles - start)
+    }
+
+    // Runs until the next instruction to execute is at pc
+    pub fn run_until_pc<B: CpuBus + ?Sized>(&mut self, bus: &mut B, pc: u16) -> Result<u64, Jam> {
+        let start = self.total_cycles;
+
+        while self.pc != pc {
+            self.step(bus)?;
+        }
+
+        Ok(self.total_cycles - start)
+    }
+
+    // Runs instructions, an interrupt sequence counts as one
+    pub fn run_instructions<B: CpuBus + ?Sized>(
+        &mut self,
+        bus: &mut B,
+        count: u64,
+    ) -> Result<u64, Jam> {
+        let start = self.total_cycles;
+
+        for _ in 0..count {
+            self.step(bus)?;
+        }
+
+        Ok(self.total_cycles - start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nes::Nes;
+    use crate::nes::bus::{FlatBus, IrqSource};
+
+    const ORIGIN: u16 = 0x0200;
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    enum Access {
+        Read(u16, u8),
+        Write(u16, u8),
+    }
+
+    use Access::{Read, Write};
+
+    // Records every bus access, one per cycle
+    #[derive(Default)]
+    struct RecordingBus {
+        bus: FlatBus,
+        log: Vec<Access>,
+        cycle: u64,             // Cycles ticked so far
+        nmi_cycle: Option<u64>, // Cycle from which the NMI line is asserted
+    }
+
+    impl CpuBus for RecordingBus {
+        fn read(&mut self, addr: u16) -> u8 {
+            let data = self.bus.read(addr);
+            self.log.push(Read(addr, data));
+
+            data
+        }
+
+        fn write(&mut self, addr: u16, data: u8) {
+            self.bus.write(addr, data);
+            self.log.push(Write(addr, data));
+        }
+
+        fn peek(&self, addr: u16) -> u8 {
+            self.bus.peek(addr)
+        }
+
+        fn tick(&mut self) {
+            self.cycle += 1;
+        }
+
+        fn nmi(&self) -> bool {
+            self.bus.nmi || self.nmi_cycle.is_some_and(|cycle| self.cycle >= cycle)
+        }
+
+        fn irq(&self) -> u8 {
+            self.bus.irq
+        }
+    }
+
+    // CPU about to run program from ORIGIN, with the stack pointer and flags left by a reset
+    fn setup(variant: Variant, program: &[u8]) -> (Cpu, RecordingBus) {
+        let mut bus = RecordingBus::default();
+        bus.bus.load(ORIGIN, program);
+
+        let mut cpu = Cpu::new(variant);
+        cpu.sp = 0xFD;
+        cpu.p = StatusFlags::UNUSED | StatusFlags::INTERRUPT_DISABLE;
+        cpu.pc = ORIGIN;
+
+        (cpu, bus)
+    }
+
+    // Runs one instruction and returns the bus accesses it made
+    fn step_log(cpu: &mut Cpu, bus: &mut RecordingBus) -> Vec<Access> {
+        bus.log.clear();
+        let cycles = cpu.step(bus).unwrap();
+        assert_eq!(cycles as usize, bus.log.len());
+
+        std::mem::take(&mut bus.log)
+    }
+
+    #[test]
+    fn read_modify_write_writes_twice() {
+        // inc $0300
+        let (mut cpu, mut bus) = setup(Variant::Ricoh2A03, &[0xEE, 0x00, 0x03]);
+        bus.bus.ram[0x0300] = 0x05;
+
+        assert_eq!(
+            step_log(&mut cpu, &mut bus),
+            [
+                Read(0x0200, 0xEE),
+                Read(0x0201, 0x00),
+                Read(0x0202, 0x03),
+                Read(0x0300, 0x05),
+                Write(0x0300, 0x05),
+                Write(0x0300, 0x06),
+            ]
+        );
+    }
+
+    #[test]
+    fn indexed_read_crossing_page() {
+        // lda $02FF,x with x = 1, the unfixed address $0200 is read first
+        let (mut cpu, mut bus) = setup(Variant::Ricoh2A03, &[0xBD, 0xFF, 0x02]);
+        cpu.x = 1;
+        bus.bus.ram[0x0300] = 0x42;
+
+        assert_eq!(
+            step_log(&mut cpu, &mut bus),
+            [
+                Read(0x0200, 0xBD),
+                Read(0x0201, 0xFF),
+                Read(0x0202, 0x02),
+                Read(0x0200, 0xBD),
+                Read(0x0300, 0x42),
+            ]
+        );
+        assert_eq!(cpu.a, 0x42);
+    }
+
+    #[test]
+    fn indexed_read_within_page() {
+        // lda $0300,x with x = 1
+        let (mut cpu, mut bus) = setup(Variant::Ricoh2A03, &[0xBD, 0x00, 0x03]);
+        cpu.x = 1;
+
+        assert_eq!(
+            step_log(&mut cpu, &mut bus),
+            [
+                Read(0x0200, 0xBD),
+                Read(0x0201, 0x00),
+                Read(0x0202, 0x03),
+                Read(0x0301, 0x00),
+            ]
+        );
+    }
+
+    #[test]
+    fn indexed_store_always_reads_first() {
+        // sta $0300,x with x = 1, no page crossed but the dummy read still happens
+        let (mut cpu, mut bus) = setup(Variant::Ricoh2A03, &[0x9D, 0x00, 0x03]);
+        cpu.x = 1;
+        cpu.a = 0x42;
+
+        assert_eq!(
+            step_log(&mut cpu, &mut bus),
+            [
+                Read(0x0200, 0x9D),
+                Read(0x0201, 0x00),
+                Read(0x0202, 0x03),
+                Read(0x0301, 0x00),
+                Write(0x0301, 0x42),
+            ]
+        );
+    }
+
+    #[test]
+    fn indexed_read_modify_write_65c02() {
+        // asl $0300,x with x = 1: no fixing cycle without a page crossed, and the operand is read
+        // twice instead of written twice
+        let (mut cpu, mut bus) = setup(Variant::Wdc65C02, &[0x1E, 0x00, 0x03]);
+        cpu.x = 1;
+        bus.bus.ram[0x0301] = 0x21;
+
+        assert_eq!(
+            step_log(&mut cpu, &mut bus),
+            [
+                Read(0x0200, 0x1E),
+                Read(0x0201, 0x00),
+                Read(0x0202, 0x03),
+                Read(0x0301, 0x21),
+                Read(0x0301, 0x21),
+                Write(0x0301, 0x42),
+            ]
+        );
+    }
+
+    const NMI_HANDLER: u16 = 0x0500;
+    const IRQ_HANDLER: u16 = 0x0400;
+
+    // Like setup, with the vectors pointing to handlers made of nops
+    fn setup_interrupts(program: &[u8]) -> (Cpu, RecordingBus) {
+        let (cpu, mut bus) = setup(Variant::Ricoh2A03, program);
+
+        bus.bus.load(0xFFFA, &NMI_HANDLER.to_le_bytes());
+        bus.bus.load(0xFFFE, &IRQ_HANDLER.to_le_bytes());
+        bus.bus.load(NMI_HANDLER, &[0xEA; 4]);
+        bus.bus.load(IRQ_HANDLER, &[0xEA; 4]);
+
+        (cpu, bus)
+    }
+
+    #[test]
+    fn irq_after_cli_waits_one_instruction() {
+        // cli, nop, nop
+        let (mut cpu, mut bus) = setup_interrupts(&[0x58, 0xEA, 0xEA]);
+        bus.bus.irq = IrqSource::EXTERNAL;
+
+        cpu.step(&mut bus).unwrap();
+        cpu.step(&mut bus).unwrap();
+        assert_eq!(cpu.pc, 0x0202);
+
+        assert_eq!(cpu.step(&mut bus), Ok(7));
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+    }
+
+    #[test]
+    fn irq_pending_before_sei_is_taken() {
+        // sei, nop with the IRQ line already asserted
+        let (mut cpu, mut bus) = setup_interrupts(&[0x78, 0xEA]);
+        cpu.p = StatusFlags::UNUSED;
+        bus.bus.irq = IrqSource::EXTERNAL;
+
+        cpu.step(&mut bus).unwrap();
+        cpu.step(&mut bus).unwrap();
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+
+        // Returns to the nop, with interrupts disabled in the pushed status
+        assert_eq!(bus.bus.ram[0x01FC..=0x01FD], [0x01, 0x02]);
+        assert_eq!(
+            bus.bus.ram[0x01FB],
+            StatusFlags::UNUSED | StatusFlags::INTERRUPT_DISABLE
+        );
+    }
+
+    #[test]
+    fn irq_after_plp_waits_one_instruction() {
+        // plp pulling a status with interrupts enabled, nop, nop
+        let (mut cpu, mut bus) = setup_interrupts(&[0x28, 0xEA, 0xEA]);
+        cpu.sp = 0xFC;
+        bus.bus.ram[0x01FD] = StatusFlags::UNUSED;
+        bus.bus.irq = IrqSource::EXTERNAL;
+
+        cpu.step(&mut bus).unwrap();
+        cpu.step(&mut bus).unwrap();
+        assert_eq!(cpu.pc, 0x0202);
+
+        cpu.step(&mut bus).unwrap();
+        assert_eq!(cpu.pc, IRQ_HANDLER);
+    }
+
+    #[test]
+    fn nmi_fires_once_per_edge() {
+        let (mut cpu, mut bus) = setup_interrupts(&[0xEA; 4]);
+        bus.bus.nmi = true;
+
+        cpu.step(&mut bus).unwrap();
+        assert_eq!(cpu.step(&mut bus), Ok(7));
+        assert_eq!(cpu.pc, NMI_HANDLER);
+
+        // The line stays asserted, the handler runs undisturbed
+        for i in 1..=3 {
+            cpu.step(&mut bus).unwrap();
+            assert_eq!(cpu.pc, NMI_HANDLER + i);
+        }
+    }
+
+    #[test]
+    fn nmi_hijacks_brk() {
+        // brk with the NMI line asserted on its 4th cycle, before the vector is chosen
+        let (mut cpu, mut bus) = setup_interrupts(&[0x00, 0x00]);
+        bus.nmi_cycle = Some(4);
+
+        assert_eq!(cpu.step(&mut bus), Ok(7));
+        assert_eq!(cpu.pc, NMI_HANDLER);
+
+        // The return address skips the padding byte and the pushed status has break set
+        assert_eq!(bus.bus.ram[0x01FC..=0x01FD], [0x02, 0x02]);
+        assert_ne!(bus.bus.ram[0x01FB] & StatusFlags::BREAK, 0);
+
+        // The NMI was consumed by the brk, the handler isn't interrupted
+        cpu.step(&mut bus).unwrap();
+        assert_eq!(cpu.pc, NMI_HANDLER + 1);
+    }
+
+    // sed, clc, lda #a, adc #value: runs the adc and returns its cycles
+    fn decimal_adc(variant: Variant, a: u8, value: u8, carry: bool) -> (Cpu, u16) {
+        let (mut cpu, mut bus) = setup(variant, &[0x69, value]);
+        cpu.a = a;
+        cpu.set_flag(StatusFlags::DECIMAL, true);
+        cpu.set_flag(StatusFlags::CARRY, carry);
+
+        let cycles = cpu.step(&mut bus).unwrap();
+
+        (cpu, cycles)
+    }
+
+    #[test]
+    fn decimal_adc_nmos() {
+        // Zero comes from the binary sum $9A, negative from the sum before the high digit is
+        // adjusted
+        let (cpu, cycles) = decimal_adc(Variant::Nmos6502, 0x99, 0x01, false);
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.has_flag(StatusFlags::CARRY));
+        assert!(!cpu.has_flag(StatusFlags::ZERO));
+        assert!(cpu.has_flag(StatusFlags::NEGATIVE));
+        assert_eq!(cycles, 2);
+
+        let (cpu, _) = decimal_adc(Variant::Nmos6502, 0x79, 0x00, true);
+        assert_eq!(cpu.a, 0x80);
+        assert!(cpu.has_flag(StatusFlags::OVERFLOW));
+        assert!(cpu.has_flag(StatusFlags::NEGATIVE));
+    }
+
+    #[test]
+    fn decimal_adc_65c02() {
+        // Negative and zero come from the result, at the cost of one cycle
+        let (cpu, cycles) = decimal_adc(Variant::Wdc65C02, 0x99, 0x01, false);
+        assert_eq!(cpu.a, 0x00);
+        assert!(cpu.has_flag(StatusFlags::CARRY));
+        assert!(cpu.has_flag(StatusFlags::ZERO));
+        assert!(!cpu.has_flag(StatusFlags::NEGATIVE));
+        assert_eq!(cycles, 3);
+    }
+
+    #[test]
+    fn decimal_ignored_by_2a03() {
+        let (cpu, cycles) = decimal_adc(Variant::Ricoh2A03, 0x99, 0x01, false);
+        assert_eq!(cpu.a, 0x9A);
+        assert_eq!(cycles, 2);
+    }
+
+    #[test]
+    fn decimal_sbc() {
+        for variant in [Variant::Nmos6502, Variant::Wdc65C02] {
+            // sbc #$01 from $00 with no borrow
+            let (mut cpu, mut bus) = setup(variant, &[0xE9, 0x01]);
+            cpu.set_flag(StatusFlags::DECIMAL, true);
+            cpu.set_flag(StatusFlags::CARRY, true);
 
-        while self.cycles != 0 {
-            self.step(bus);
+            cpu.step(&mut bus).unwrap();
+            assert_eq!(cpu.a, 0x99, "{:?}", variant);
+            assert!(!cpu.has_flag(StatusFlags::CARRY), "{:?}", variant);
         }
     }
+
+    #[test]
+    fn indirect_jump_across_page() {
+        // jmp ($02FF), the NMOS 6502 takes the high byte from $0200 instead of $0300
+        let program = [0x6C, 0xFF, 0x02];
+
+        let (mut cpu, mut bus) = setup(Variant::Nmos6502, &program);
+        bus.bus.load(0x02FF, &[0x34, 0x12]);
+        assert_eq!(cpu.step(&mut bus), Ok(5));
+        assert_eq!(cpu.pc, 0x6C34);
+
+        let (mut cpu, mut bus) = setup(Variant::Wdc65C02, &program);
+        bus.bus.load(0x02FF, &[0x34, 0x12]);
+        assert_eq!(cpu.step(&mut bus), Ok(6));
+        assert_eq!(cpu.pc, 0x1234);
+    }
+
+    // Runs a 65C02 program of one instruction on the zero page byte $10, returns it afterwards
+    fn run_65c02(program: &[u8], a: u8, value: u8) -> (Cpu, u8) {
+        let (mut cpu, mut bus) = setup(Variant::Wdc65C02, program);
+        cpu.a = a;
+        bus.bus.ram[0x0010] = value;
+
+        cpu.step(&mut bus).unwrap();
+
+        (cpu, bus.bus.ram[0x0010])
+    }
+
+    #[test]
+    fn store_zero() {
+        // stz $10
+        assert_eq!(run_65c02(&[0x64, 0x10], 0x00, 0xFF).1, 0x00);
+    }
+
+    #[test]
+    fn test_and_reset_bits() {
+        // trb $10, zero is set from a & memory
+        let (cpu, value) = run_65c02(&[0x14, 0x10], 0x0F, 0x3C);
+        assert_eq!(value, 0x30);
+        assert!(!cpu.has_flag(StatusFlags::ZERO));
+    }
+
+    #[test]
+    fn test_and_set_bits() {
+        // tsb $10
+        let (cpu, value) = run_65c02(&[0x04, 0x10], 0x0F, 0x30);
+        assert_eq!(value, 0x3F);
+        assert!(cpu.has_flag(StatusFlags::ZERO));
+    }
+
+    #[test]
+    fn reset_and_set_memory_bit() {
+        // rmb3 $10, smb3 $10
+        assert_eq!(run_65c02(&[0x37, 0x10], 0x00, 0xFF).1, 0xF7);
+        assert_eq!(run_65c02(&[0xB7, 0x10], 0x00, 0x00).1, 0x08);
+    }
+
+    #[test]
+    fn branch_on_bit() {
+        // bbr0 $10,+4 and bbs0 $10,+4 with bit 0 of $10 reset
+        let (cpu, _) = run_65c02(&[0x0F, 0x10, 0x04], 0x00, 0xFE);
+        assert_eq!(cpu.pc, 0x0207);
+
+        let (cpu, _) = run_65c02(&[0x8F, 0x10, 0x04], 0x00, 0xFE);
+        assert_eq!(cpu.pc, 0x0203);
+    }
+
+    #[test]
+    fn step_counts_dma_cycles() {
+        let mut nes = Nes::from_program("lda #$02\nsta $4014\nnop").unwrap();
+        nes.reset();
+
+        nes.cpu.step(&mut nes.bus).unwrap();
+        nes.cpu.step(&mut nes.bus).unwrap();
+
+        let start = nes.cpu.total_cycles();
+        let cycles = nes.cpu.step(&mut nes.bus).unwrap();
+
+        // The OAM DMA halts the CPU on the opcode fetch of the nop, 513 or 514 cycles on top of
+        // its 2
+        assert!(matches!(cycles, 515 | 516), "{} cycles", cycles);
+        assert_eq!(cycles as u64, nes.cpu.total_cycles() - start);
+    }
 }
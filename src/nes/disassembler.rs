@@ -0,0 +1,382 @@
+use super::bus::{ADDR_IRQ_VECTOR, ADDR_NMI_VECTOR, ADDR_PRG_ROM, ADDR_RESET_VECTOR};
+use super::instructions::{AddrMode, get_instruction};
+use std::collections::BTreeMap;
+use std::fmt;
+
+const VECTORS: [(usize, &str); 3] = [
+    (ADDR_NMI_VECTOR, "nmi"),
+    (ADDR_RESET_VECTOR, "reset"),
+    (ADDR_IRQ_VECTOR, "irq"),
+];
+
+const BYTES_PER_DATA_LINE: usize = 16;
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Byte {
+    Data,
+    Opcode,
+    Operand,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LineKind {
+    Code,
+    Data,
+}
+
+pub struct Line {
+    pub addr: u16,
+    pub bytes: Vec<u8>,
+    pub kind: LineKind,
+    pub text: String, // ca65 statement, without the label
+}
+
+pub struct Disassembly {
+    pub origin: u16,
+    pub labels: BTreeMap<u16, String>,
+    pub lines: Vec<Line>,
+}
+
+// Disassembles a 16 KiB or 32 KiB PRG image mapped at the end of the address space. Code is found
+// by following the control flow from the vectors, anything never reached is kept as data. Only
+// official opcodes are decoded so the output reassembles to the same bytes.
+pub fn disassemble(prg_rom: &[u8]) -> Result<Disassembly, String> {
+    if prg_rom.is_empty() || prg_rom.len() > 0x10000 - ADDR_PRG_ROM {
+        return Err(format!(
+            "Unsupported PRG ROM size: {} bytes (at most 32 KiB)",
+            prg_rom.len()
+        ));
+    }
+
+    let origin = (0x10000 - prg_rom.len()) as u16;
+
+    let mut disassembler = Disassembler {
+        prg_rom,
+        origin,
+        bytes: vec![Byte::Data; prg_rom.len()],
+        labels: BTreeMap::new(),
+    };
+
+    disassembler.trace();
+    disassembler.label_references();
+
+    let lines = disassembler.lines();
+
+    Ok(Disassembly {
+        origin,
+        labels: disassembler.labels,
+        lines,
+    })
+}
+
+// Operand of an instruction in ca65 syntax, around the already formatted address or value: "$12",
+// "$1234" or a label. Bit branches take the zero page address and the target, separated by a comma.
+pub fn format_operand(name: &str, mode: AddrMode, operand: &str) -> String {
+    match mode {
+        AddrMode::Imp if matches!(name, "asl" | "lsr" | "rol" | "ror") => "a".to_string(),
+        AddrMode::Imp => String::new(),
+        AddrMode::Imm => format!("#{}", operand),
+        AddrMode::Zp0 | AddrMode::Rel | AddrMode::Abs | AddrMode::Zpr => operand.to_string(),
+        AddrMode::Zpx | AddrMode::Abx => format!("{},x", operand),
+        AddrMode::Zpy | AddrMode::Aby => format!("{},y", operand),
+        AddrMode::Ind | AddrMode::Izp => format!("({})", operand),
+        AddrMode::Izx | AddrMode::Iax => format!("({},x)", operand),
+        AddrMode::Izy => format!("({}),y", operand),
+    }
+}
+
+struct Disassembler<'a> {
+    prg_rom: &'a [u8],
+    origin: u16,
+    bytes: Vec<Byte>,
+    labels: BTreeMap<u16, String>,
+}
+
+impl Disassembler<'_> {
+    fn offset(&self, addr: u16) -> Option<usize> {
+        addr.checked_sub(self.origin).map(|offset| offset as usize)
+    }
+
+    fn peek(&self, addr: u16) -> u8 {
+        self.prg_rom[(addr - self.origin) as usize]
+    }
+
+    fn peek_word(&self, addr: u16) -> u16 {
+        ((self.peek(addr.wrapping_add(1)) as u16) << 8) | self.peek(addr) as u16
+    }
+
+    // Operand of the instruction at addr, the branch target for relative addressing
+    fn operand(&self, addr: u16, mode: AddrMode) -> u16 {
+        match mode.operand_len() {
+            0 => 0,
+            1 if mode == AddrMode::Rel => addr
+                .wrapping_add(2)
+                .wrapping_add(self.peek(addr + 1) as i8 as u16),
+            1 => self.peek(addr + 1) as u16,
+            _ => self.peek_word(addr + 1),
+        }
+    }
+
+    // Marks every instruction reachable from the vectors
+    fn trace(&mut self) {
+        let mut pending = vec![];
+
+        for (vector, name) in VECTORS {
+            let target = self.peek_word(vector as u16);
+
+            if self.offset(target).is_some() {
+                self.labels.entry(target).or_insert(name.to_string());
+                pending.push(target);
+            }
+        }
+
+        while let Some(mut addr) = pending.pop() {
+            // Stops on code already traced, unofficial opcodes and instructions overlapping others
+            while let Some(offset) = self.offset(addr)
+                && self.bytes[offset] == Byte::Data
+            {
+                let instruction = get_instruction(self.peek(addr));
+                let len = instruction.byte_len() as usize;
+
+                if !instruction.official
+                    || offset + len > self.bytes.len()
+                    || self.bytes[offset..offset + len].contains(&Byte::Operand)
+                    || self.bytes[offset..offset + len].contains(&Byte::Opcode)
+                {
+                    break;
+                }
+
+                self.bytes[offset] = Byte::Opcode;
+                self.bytes[offset + 1..offset + len].fill(Byte::Operand);
+
+                let operand = self.operand(addr, instruction.mode);
+
+                match (instruction.name, instruction.mode) {
+                    (_, AddrMode::Rel) | ("jsr", _) => pending.push(operand),
+                    ("jmp", AddrMode::Abs) => {
+                        pending.push(operand);
+                        break;
+                    }
+                    ("jmp", _) | ("rts", _) | ("rti", _) | ("brk", _) => break,
+                    _ => {}
+                }
+
+                if offset + len == self.bytes.len() {
+                    break;
+                }
+
+                addr += len as u16;
+            }
+        }
+    }
+
+    // Labels the addresses in the image used by absolute and relative operands, unless they point
+    // inside an instruction
+    fn label_references(&mut self) {
+        for (offset, &byte) in self.bytes.iter().enumerate() {
+            if byte != Byte::Opcode {
+                continue;
+            }
+
+            let addr = self.origin + offset as u16;
+            let instruction = get_instruction(self.peek(addr));
+
+            if instruction.mode.operand_len() == 2 || instruction.mode == AddrMode::Rel {
+                let target = self.operand(addr, instruction.mode);
+
+                if let Some(target_offset) = self.offset(target)
+                    && self.bytes[target_offset] != Byte::Operand
+                {
+                    self.labels
+                        .entry(target)
+                        .or_insert_with(|| format!("L{:04X}", target));
+                }
+            }
+        }
+
+        // Labels of vectors pointing outside of the image or at operands
+        self.labels
+            .retain(|&addr, _| self.bytes[(addr - self.origin) as usize] != Byte::Operand);
+    }
+
+    fn address(&self, addr: u16) -> String {
+        match self.labels.get(&addr) {
+            Some(label) => label.clone(),
+            None => format!("${:04X}", addr),
+        }
+    }
+
+    fn instruction_text(&self, addr: u16) -> String {
+        let instruction = get_instruction(self.peek(addr));
+        let name = instruction.name;
+        let value = self.operand(addr, instruction.mode);
+
+        // Absolute operands in the zero page need a: so they don't get reassembled as zero page
+        let absolute = || match value {
+            0x00..=0xFF => format!("a:${:04X}", value),
+            _ => self.address(value),
+        };
+
+        let operand = match instruction.mode {
+            AddrMode::Imp => String::new(),
+            AddrMode::Imm
+            | AddrMode::Zp0
+            | AddrMode::Zpx
+            | AddrMode::Zpy
+            | AddrMode::Izx
+            | AddrMode::Izy
+            | AddrMode::Izp => format!("${:02X}", value),
+            AddrMode::Abs | AddrMode::Abx | AddrMode::Aby => absolute(),
+            AddrMode::Rel | AddrMode::Ind | AddrMode::Iax => self.address(value),
+            AddrMode::Zpr => format!("${:02X},{}", value & 0xFF, self.address(value)),
+        };
+
+        format!(
+            "{} {}",
+            name,
+            format_operand(name, instruction.mode, &operand)
+        )
+        .trim_end()
+        .to_string()
+    }
+
+    // Length of the data line starting at offset, lines end before code, labels and vectors
+    fn data_len(&self, offset: usize) -> usize {
+        let start = self.origin as usize + offset;
+        let mut len = 1;
+
+        while len < BYTES_PER_DATA_LINE
+            && offset + len < self.bytes.len()
+            && self.bytes[offset + len] == Byte::Data
+            && !self.labels.contains_key(&((start + len) as u16))
+            && start + len != ADDR_NMI_VECTOR
+        {
+            len += 1;
+        }
+
+        len
+    }
+
+    fn lines(&self) -> Vec<Line> {
+        let mut lines = vec![];
+        let mut offset = 0;
+
+        while offset < self.bytes.len() {
+            let addr = self.origin + offset as u16;
+
+            let (kind, len, text) = if self.bytes[offset] == Byte::Opcode {
+                let len = get_instruction(self.peek(addr)).byte_len() as usize;
+
+                (LineKind::Code, len, self.instruction_text(addr))
+            } else if VECTORS.iter().any(|&(vector, _)| vector == addr as usize)
+                && self.bytes[offset + 1] == Byte::Data
+                && !self.labels.contains_key(&(addr + 1))
+            {
+                (
+                    LineKind::Data,
+                    2,
+                    format!(".word {}", self.address(self.peek_word(addr))),
+                )
+            } else {
+                let len = self.data_len(offset);
+                let bytes: Vec<String> = self.prg_rom[offset..offset + len]
+                    .iter()
+                    .map(|byte| format!("${:02X}", byte))
+                    .collect();
+
+                (LineKind::Data, len, format!(".byte {}", bytes.join(",")))
+            };
+
+            lines.push(Line {
+                addr,
+                bytes: self.prg_rom[offset..offset + len].to_vec(),
+                kind,
+                text,
+            });
+
+            offset += len;
+        }
+
+        lines
+    }
+}
+
+// ca65 source of the whole image
+impl fmt::Display for Disassembly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "        .org ${:04X}", self.origin)?;
+
+        for line in &self.lines {
+            if let Some(label) = self.labels.get(&line.addr) {
+                writeln!(f, "{}:", label)?;
+            }
+
+            writeln!(f, "        {}", line.text)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nes::assembler::{PRG_ROM_SIZE, assemble};
+
+    // Disassembles the image, assembles the listing back and compares the bytes
+    fn round_trip(prg_rom: &[u8]) {
+        let disassembly = disassemble(prg_rom).unwrap();
+        let source = disassembly.to_string();
+        let assembly = assemble(&source).unwrap_or_else(|e| panic!("{}\n{}", e, source));
+
+        assert_eq!(&assembly.prg_rom[PRG_ROM_SIZE - prg_rom.len()..], prg_rom);
+    }
+
+    #[test]
+    fn round_trip_program() {
+        let source = "
+            .org $C000
+            reset:  sei
+                    ldx #$FF
+                    txs
+                    lda #0
+                    sta $2000
+            loop:   lda $10,x
+                    sta a:$0200,y
+                    lda ($20),y
+                    jsr sub
+                    asl a
+                    bne loop
+                    jmp (vector)
+            sub:    inc $0300
+                    rts
+            vector: .word reset
+            nmi:
+            irq:    rti
+                    .byte 1, 2, 3, $FF
+        ";
+        let assembly = assemble(source).unwrap();
+
+        round_trip(&assembly.prg_rom[PRG_ROM_SIZE - 0x4000..]);
+        round_trip(&assembly.prg_rom);
+    }
+
+    #[test]
+    fn round_trip_random_images() {
+        // Linear congruential generator, deterministic so failures are reproducible
+        let mut seed: u64 = 0x2A03;
+        let mut next = || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 56) as u8
+        };
+
+        for i in 0..50 {
+            let size = if i % 2 == 0 { 0x4000 } else { 0x8000 };
+            let prg_rom: Vec<u8> = (0..size).map(|_| next()).collect();
+
+            round_trip(&prg_rom);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use super::palette::Palette;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+// RGB image, for screenshots and the debug viewers
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub rgb: Vec<u8>, // 3 bytes per pixel, row after row
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            rgb: vec![0; width * height * 3],
+        }
+    }
+
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        let i = (y * self.width + x) * 3;
+        [self.rgb[i], self.rgb[i + 1], self.rgb[i + 2]]
+    }
+
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: [u8; 3]) {
+        let i = (y * self.width + x) * 3;
+        self.rgb[i..i + 3].copy_from_slice(&color);
+    }
+
+    pub fn from_frame(frame: &[u16], width: usize, palette: &Palette) -> Self {
+        Self {
+            width,
+            height: frame.len() / width,
+            rgb: palette.to_rgb(frame),
+        }
+    }
+
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Self {
+        let mut image = Self::new(width, height);
+
+        for row in 0..height {
+            let start = ((y + row) * self.width + x) * 3;
+            image.rgb[row * width * 3..(row + 1) * width * 3]
+                .copy_from_slice(&self.rgb[start..start + width * 3]);
+        }
+
+        image
+    }
+
+    // Nearest neighbour upscaling by an integer factor, keeps the pixels sharp
+    pub fn scale(&self, factor: usize) -> Self {
+        let mut image = Self::new(self.width * factor, self.height * factor);
+
+        for y in 0..image.height {
+            for x in 0..image.width {
+                image.set_pixel(x, y, self.pixel(x / factor, y / factor));
+            }
+        }
+
+        image
+    }
+
+    // Outline of a rectangle, the parts crossing the right or bottom edge wrap around
+    pub fn outline(&mut self, x: usize, y: usize, width: usize, height: usize, color: [u8; 3]) {
+        for dx in 0..width {
+            self.set_pixel((x + dx) % self.width, y % self.height, color);
+            self.set_pixel((x + dx) % self.width, (y + height - 1) % self.height, color);
+        }
+
+        for dy in 0..height {
+            self.set_pixel(x % self.width, (y + dy) % self.height, color);
+            self.set_pixel((x + width - 1) % self.width, (y + dy) % self.height, color);
+        }
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), io::Error> {
+        let file = File::create(path)?;
+
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(file), self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.rgb)?;
+
+        Ok(())
+    }
+}
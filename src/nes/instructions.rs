@@ -1,17 +1,40 @@
-use super::bus::Bus;
-use super::cpu::Cpu;
+use super::bus::CpuBus;
+use super::cpu::{Cpu, Variant};
 
 #[derive(Copy, Clone)]
 pub struct Instruction {
     pub name: &'static str,
-    pub addr: fn(&mut Cpu, &mut Bus) -> u8,
-    pub operate: fn(&mut Cpu, &mut Bus) -> u8,
     pub mode: AddrMode,
-    pub cycles: u8,
+    pub cycles: u8,     // Base cycles, without page crossing or branch penalties
+    pub official: bool, // Documented by the manufacturer
+    pub access: Access, // How the operand is accessed in memory
 }
 
-#[derive(Copy, Clone, PartialEq, Eq)]
+impl Instruction {
+    // Opcode and operand bytes
+    pub fn byte_len(&self) -> u16 {
+        1 + self.mode.operand_len()
+    }
+
+    // Reads take one more cycle when the indexing crosses a page, writes and read-modify-writes
+    // always spend it
+    pub fn page_cross_penalty(&self) -> bool {
+        self.access == Access::Read
+            && matches!(self.mode, AddrMode::Abx | AddrMode::Aby | AddrMode::Izy)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Access {
+    None, // Doesn't access an operand in memory: implied, stack, jumps and branches
+    Read,
+    Write,
+    ReadModifyWrite,
+}
+
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
 pub enum AddrMode {
+    #[default]
     Imp,
     Imm,
     Zp0,
@@ -24,6 +47,31 @@ pub enum AddrMode {
     Ind,
     Izx,
     Izy,
+    Izp, // Zero page indirect, 65C02 only
+    Iax, // Absolute indexed with x indirect, 65C02 only
+    Zpr, // Zero page then relative, 65C02 bit branches only
+}
+
+impl AddrMode {
+    pub fn operand_len(&self) -> u16 {
+        match self {
+            AddrMode::Imp => 0,
+            AddrMode::Imm
+            | AddrMode::Zp0
+            | AddrMode::Zpx
+            | AddrMode::Zpy
+            | AddrMode::Rel
+            | AddrMode::Izx
+            | AddrMode::Izy
+            | AddrMode::Izp => 1,
+            AddrMode::Abs
+            | AddrMode::Abx
+            | AddrMode::Aby
+            | AddrMode::Ind
+            | AddrMode::Iax
+            | AddrMode::Zpr => 2,
+        }
+    }
 }
 
 pub fn get_instruction(opcode: u8) -> Instruction {
@@ -33,1829 +81,4193 @@ pub fn get_instruction(opcode: u8) -> Instruction {
     INSTRUCTIONS[row][col]
 }
 
+// The 2A03 decodes the same opcodes as the NMOS 6502, only decimal mode is missing
+pub fn get_variant_instruction(variant: Variant, opcode: u8) -> Instruction {
+    let row = (opcode / 16) as usize;
+    let col = (opcode % 16) as usize;
+
+    match variant {
+        Variant::Ricoh2A03 | Variant::Nmos6502 => INSTRUCTIONS[row][col],
+        Variant::Wdc65C02 => INSTRUCTIONS_65C02[row][col],
+    }
+}
+
+// Decodes and runs an NMOS 6502 opcode. Unlike a table of function pointers, a match can be
+// generic over the bus, so the helpers and the bus accesses get inlined for a concrete bus.
+pub(super) fn execute<B: CpuBus + ?Sized>(cpu: &mut Cpu, bus: &mut B, opcode: u8) {
+    // Runs the addressing mode then the operation
+    macro_rules! op {
+        ($addr:ident, $operate:ident) => {{
+            cpu.$addr(bus);
+            cpu.$operate(bus);
+        }};
+    }
+
+    match opcode {
+        0x00 => op!(imm, brk),
+        0x01 => op!(izx, ora),
+        0x02 | 0x12 | 0x22 | 0x32 | 0x42 | 0x52 | 0x62 | 0x72 | 0x92 | 0xB2 | 0xD2 | 0xF2 => {
+            op!(imp, jam)
+        }
+        0x03 => op!(izx, slo),
+        0x04 | 0x44 | 0x64 => op!(zp0, nop),
+        0x05 => op!(zp0, ora),
+        0x06 => op!(zp0, asl),
+        0x07 => op!(zp0, slo),
+        0x08 => op!(imp, php),
+        0x09 => op!(imm, ora),
+        0x0A => op!(imp, asl),
+        0x0B | 0x2B => op!(imm, anc),
+        0x0C => op!(abs, nop),
+        0x0D => op!(abs, ora),
+        0x0E => op!(abs, asl),
+        0x0F => op!(abs, slo),
+        0x10 => op!(rel, bpl),
+        0x11 => op!(izy, ora),
+        0x13 => op!(izy, slo),
+        0x14 | 0x34 | 0x54 | 0x74 | 0xD4 | 0xF4 => op!(zpx, nop),
+        0x15 => op!(zpx, ora),
+        0x16 => op!(zpx, asl),
+        0x17 => op!(zpx, slo),
+        0x18 => op!(imp, clc),
+        0x19 => op!(aby, ora),
+        0x1A | 0x3A | 0x5A | 0x7A | 0xDA | 0xEA | 0xFA => op!(imp, nop),
+        0x1B => op!(aby, slo),
+        0x1C | 0x3C | 0x5C | 0x7C | 0xDC | 0xFC => op!(abx, nop),
+        0x1D => op!(abx, ora),
+        0x1E => op!(abx, asl),
+        0x1F => op!(abx, slo),
+        // The target is read by Cpu::jsr itself, around the pushes of the return address
+        0x20 => op!(imm, jsr),
+        0x21 => op!(izx, and),
+        0x23 => op!(izx, rla),
+        0x24 => op!(zp0, bit),
+        0x25 => op!(zp0, and),
+        0x26 => op!(zp0, rol),
+        0x27 => op!(zp0, rla),
+        0x28 => op!(imp, plp),
+        0x29 => op!(imm, and),
+        0x2A => op!(imp, rol),
+        0x2C => op!(abs, bit),
+        0x2D => op!(abs, and),
+        0x2E => op!(abs, rol),
+        0x2F => op!(abs, rla),
+        0x30 => op!(rel, bmi),
+        0x31 => op!(izy, and),
+        0x33 => op!(izy, rla),
+        0x35 => op!(zpx, and),
+        0x36 => op!(zpx, rol),
+        0x37 => op!(zpx, rla),
+        0x38 => op!(imp, sec),
+        0x39 => op!(aby, and),
+        0x3B => op!(aby, rla),
+        0x3D => op!(abx, and),
+        0x3E => op!(abx, rol),
+        0x3F => op!(abx, rla),
+        0x40 => op!(imp, rti),
+        0x41 => op!(izx, eor),
+        0x43 => op!(izx, sre),
+        0x45 => op!(zp0, eor),
+        0x46 => op!(zp0, lsr),
+        0x47 => op!(zp0, sre),
+        0x48 => op!(imp, pha),
+        0x49 => op!(imm, eor),
+        0x4A => op!(imp, lsr),
+        0x4B => op!(imm, alr),
+        0x4C => op!(abs, jmp),
+        0x4D => op!(abs, eor),
+        0x4E => op!(abs, lsr),
+        0x4F => op!(abs, sre),
+        0x50 => op!(rel, bvc),
+        0x51 => op!(izy, eor),
+        0x53 => op!(izy, sre),
+        0x55 => op!(zpx, eor),
+        0x56 => op!(zpx, lsr),
+        0x57 => op!(zpx, sre),
+        0x58 => op!(imp, cli),
+        0x59 => op!(aby, eor),
+        0x5B => op!(aby, sre),
+        0x5D => op!(abx, eor),
+        0x5E => op!(abx, lsr),
+        0x5F => op!(abx, sre),
+        0x60 => op!(imp, rts),
+        0x61 => op!(izx, adc),
+        0x63 => op!(izx, rra),
+        0x65 => op!(zp0, adc),
+        0x66 => op!(zp0, ror),
+        0x67 => op!(zp0, rra),
+        0x68 => op!(imp, pla),
+        0x69 => op!(imm, adc),
+        0x6A => op!(imp, ror),
+        0x6B => op!(imm, arr),
+        0x6C => op!(ind, jmp),
+        0x6D => op!(abs, adc),
+        0x6E => op!(abs, ror),
+        0x6F => op!(abs, rra),
+        0x70 => op!(rel, bvs),
+        0x71 => op!(izy, adc),
+        0x73 => op!(izy, rra),
+        0x75 => op!(zpx, adc),
+        0x76 => op!(zpx, ror),
+        0x77 => op!(zpx, rra),
+        0x78 => op!(imp, sei),
+        0x79 => op!(aby, adc),
+        0x7B => op!(aby, rra),
+        0x7D => op!(abx, adc),
+        0x7E => op!(abx, ror),
+        0x7F => op!(abx, rra),
+        0x80 | 0x82 | 0x89 | 0xC2 | 0xE2 => op!(imm, nop),
+        0x81 => op!(izx, sta),
+        0x83 => op!(izx, sax),
+        0x84 => op!(zp0, sty),
+        0x85 => op!(zp0, sta),
+        0x86 => op!(zp0, stx),
+        0x87 => op!(zp0, sax),
+        0x88 => op!(imp, dey),
+        0x8A => op!(imp, txa),
+        0x8B => op!(imm, xaa),
+        0x8C => op!(abs, sty),
+        0x8D => op!(abs, sta),
+        0x8E => op!(abs, stx),
+        0x8F => op!(abs, sax),
+        0x90 => op!(rel, bcc),
+        0x91 => op!(izy, sta),
+        0x93 => op!(izy, ahx),
+        0x94 => op!(zpx, sty),
+        0x95 => op!(zpx, sta),
+        0x96 => op!(zpy, stx),
+        0x97 => op!(zpy, sax),
+        0x98 => op!(imp, tya),
+        0x99 => op!(aby, sta),
+        0x9A => op!(imp, txs),
+        0x9B => op!(aby, tas),
+        0x9C => op!(abx, shy),
+        0x9D => op!(abx, sta),
+        0x9E => op!(aby, shx),
+        0x9F => op!(aby, ahx),
+        0xA0 => op!(imm, ldy),
+        0xA1 => op!(izx, lda),
+        0xA2 => op!(imm, ldx),
+        0xA3 => op!(izx, lax),
+        0xA4 => op!(zp0, ldy),
+        0xA5 => op!(zp0, lda),
+        0xA6 => op!(zp0, ldx),
+        0xA7 => op!(zp0, lax),
+        0xA8 => op!(imp, tay),
+        0xA9 => op!(imm, lda),
+        0xAA => op!(imp, tax),
+        0xAB => op!(imm, lxa),
+        0xAC => op!(abs, ldy),
+        0xAD => op!(abs, lda),
+        0xAE => op!(abs, ldx),
+        0xAF => op!(abs, lax),
+        0xB0 => op!(rel, bcs),
+        0xB1 => op!(izy, lda),
+        0xB3 => op!(izy, lax),
+        0xB4 => op!(zpx, ldy),
+        0xB5 => op!(zpx, lda),
+        0xB6 => op!(zpy, ldx),
+        0xB7 => op!(zpy, lax),
+        0xB8 => op!(imp, clv),
+        0xB9 => op!(aby, lda),
+        0xBA => op!(imp, tsx),
+        0xBB => op!(aby, las),
+        0xBC => op!(abx, ldy),
+        0xBD => op!(abx, lda),
+        0xBE => op!(aby, ldx),
+        0xBF => op!(aby, lax),
+        0xC0 => op!(imm, cpy),
+        0xC1 => op!(izx, cmp),
+        0xC3 => op!(izx, dcp),
+        0xC4 => op!(zp0, cpy),
+        0xC5 => op!(zp0, cmp),
+        0xC6 => op!(zp0, dec),
+        0xC7 => op!(zp0, dcp),
+        0xC8 => op!(imp, iny),
+        0xC9 => op!(imm, cmp),
+        0xCA => op!(imp, dex),
+        0xCB => op!(imm, axs),
+        0xCC => op!(abs, cpy),
+        0xCD => op!(abs, cmp),
+        0xCE => op!(abs, dec),
+        0xCF => op!(abs, dcp),
+        0xD0 => op!(rel, bne),
+        0xD1 => op!(izy, cmp),
+        0xD3 => op!(izy, dcp),
+        0xD5 => op!(zpx, cmp),
+        0xD6 => op!(zpx, dec),
+        0xD7 => op!(zpx, dcp),
+        0xD8 => op!(imp, cld),
+        0xD9 => op!(aby, cmp),
+        0xDB => op!(aby, dcp),
+        0xDD => op!(abx, cmp),
+        0xDE => op!(abx, dec),
+        0xDF => op!(abx, dcp),
+        0xE0 => op!(imm, cpx),
+        0xE1 => op!(izx, sbc),
+        0xE3 => op!(izx, isc),
+        0xE4 => op!(zp0, cpx),
+        0xE5 => op!(zp0, sbc),
+        0xE6 => op!(zp0, inc),
+        0xE7 => op!(zp0, isc),
+        0xE8 => op!(imp, inx),
+        0xE9 | 0xEB => op!(imm, sbc),
+        0xEC => op!(abs, cpx),
+        0xED => op!(abs, sbc),
+        0xEE => op!(abs, inc),
+        0xEF => op!(abs, isc),
+        0xF0 => op!(rel, beq),
+        0xF1 => op!(izy, sbc),
+        0xF3 => op!(izy, isc),
+        0xF5 => op!(zpx, sbc),
+        0xF6 => op!(zpx, inc),
+        0xF7 => op!(zpx, isc),
+        0xF8 => op!(imp, sed),
+        0xF9 => op!(aby, sbc),
+        0xFB => op!(aby, isc),
+        0xFD => op!(abx, sbc),
+        0xFE => op!(abx, inc),
+        0xFF => op!(abx, isc),
+    }
+}
+
+// Decodes and runs a 65C02 opcode
+pub(super) fn execute_65c02<B: CpuBus + ?Sized>(cpu: &mut Cpu, bus: &mut B, opcode: u8) {
+    // Runs the addressing mode then the operation
+    macro_rules! op {
+        ($addr:ident, $operate:ident) => {{
+            cpu.$addr(bus);
+            cpu.$operate(bus);
+        }};
+    }
+
+    match opcode {
+        0x00 => op!(imm, brk),
+        0x01 => op!(izx, ora),
+        0x02 | 0x22 | 0x42 | 0x62 | 0x82 | 0xC2 | 0xE2 => op!(imm, nop),
+        0x03 | 0x0B | 0x13 | 0x1B | 0x23 | 0x2B | 0x33 | 0x3B | 0x43 | 0x4B | 0x53 | 0x5B
+        | 0x63 | 0x6B | 0x73 | 0x7B | 0x83 | 0x8B | 0x93 | 0x9B | 0xA3 | 0xAB | 0xB3 | 0xBB
+        | 0xC3 | 0xD3 | 0xE3 | 0xEB | 0xF3 | 0xFB => op!(one, nop),
+        0x04 => op!(zp0, tsb),
+        0x05 => op!(zp0, ora),
+        0x06 => op!(zp0, asl),
+        0x07 | 0x17 | 0x27 | 0x37 | 0x47 | 0x57 | 0x67 | 0x77 => op!(zp0, rmb),
+        0x08 => op!(imp, php),
+        0x09 => op!(imm, ora),
+        0x0A => op!(imp, asl),
+        0x0C => op!(abs, tsb),
+        0x0D => op!(abs, ora),
+        0x0E => op!(abs, asl),
+        0x0F | 0x1F | 0x2F | 0x3F | 0x4F | 0x5F | 0x6F | 0x7F => op!(zpr, bbr),
+        0x10 => op!(rel, bpl),
+        0x11 => op!(izy, ora),
+        0x12 => op!(izp, ora),
+        0x14 => op!(zp0, trb),
+        0x15 => op!(zpx, ora),
+        0x16 => op!(zpx, asl),
+        0x18 => op!(imp, clc),
+        0x19 => op!(aby, ora),
+        0x1A => op!(imp, inc),
+        0x1C => op!(abs, trb),
+        0x1D => op!(abx, ora),
+        0x1E => op!(abx, asl),
+        // The target is read by Cpu::jsr itself, around the pushes of the return address
+        0x20 => op!(imm, jsr),
+        0x21 => op!(izx, and),
+        0x24 => op!(zp0, bit),
+        0x25 => op!(zp0, and),
+        0x26 => op!(zp0, rol),
+        0x28 => op!(imp, plp),
+        0x29 => op!(imm, and),
+        0x2A => op!(imp, rol),
+        0x2C => op!(abs, bit),
+        0x2D => op!(abs, and),
+        0x2E => op!(abs, rol),
+        0x30 => op!(rel, bmi),
+        0x31 => op!(izy, and),
+        0x32 => op!(izp, and),
+        0x34 => op!(zpx, bit),
+        0x35 => op!(zpx, and),
+        0x36 => op!(zpx, rol),
+        0x38 => op!(imp, sec),
+        0x39 => op!(aby, and),
+        0x3A => op!(imp, dec),
+        0x3C => op!(abx, bit),
+        0x3D => op!(abx, and),
+        0x3E => op!(abx, rol),
+        0x40 => op!(imp, rti),
+        0x41 => op!(izx, eor),
+        0x44 => op!(zp0, nop),
+        0x45 => op!(zp0, eor),
+        0x46 => op!(zp0, lsr),
+        0x48 => op!(imp, pha),
+        0x49 => op!(imm, eor),
+        0x4A => op!(imp, lsr),
+        0x4C => op!(abs, jmp),
+        0x4D => op!(abs, eor),
+        0x4E => op!(abs, lsr),
+        0x50 => op!(rel, bvc),
+        0x51 => op!(izy, eor),
+        0x52 => op!(izp, eor),
+        0x54 | 0xD4 | 0xF4 => op!(zpx, nop),
+        0x55 => op!(zpx, eor),
+        0x56 => op!(zpx, lsr),
+        0x58 => op!(imp, cli),
+        0x59 => op!(aby, eor),
+        0x5A => op!(imp, phy),
+        0x5C => op!(abs, nop_long),
+        0x5D => op!(abx, eor),
+        0x5E => op!(abx, lsr),
+        0x60 => op!(imp, rts),
+        0x61 => op!(izx, adc),
+        0x64 => op!(zp0, stz),
+        0x65 => op!(zp0, adc),
+        0x66 => op!(zp0, ror),
+        0x68 => op!(imp, pla),
+        0x69 => op!(imm, adc),
+        0x6A => op!(imp, ror),
+        0x6C => op!(ind, jmp),
+        0x6D => op!(abs, adc),
+        0x6E => op!(abs, ror),
+        0x70 => op!(rel, bvs),
+        0x71 => op!(izy, adc),
+        0x72 => op!(izp, adc),
+        0x74 => op!(zpx, stz),
+        0x75 => op!(zpx, adc),
+        0x76 => op!(zpx, ror),
+        0x78 => op!(imp, sei),
+        0x79 => op!(aby, adc),
+        0x7A => op!(imp, ply),
+        0x7C => op!(iax, jmp),
+        0x7D => op!(abx, adc),
+        0x7E => op!(abx, ror),
+        0x80 => op!(rel, bra),
+        0x81 => op!(izx, sta),
+        0x84 => op!(zp0, sty),
+        0x85 => op!(zp0, sta),
+        0x86 => op!(zp0, stx),
+        0x87 | 0x97 | 0xA7 | 0xB7 | 0xC7 | 0xD7 | 0xE7 | 0xF7 => op!(zp0, smb),
+        0x88 => op!(imp, dey),
+        0x89 => op!(imm, bit),
+        0x8A => op!(imp, txa),
+        0x8C => op!(abs, sty),
+        0x8D => op!(abs, sta),
+        0x8E => op!(abs, stx),
+        0x8F | 0x9F | 0xAF | 0xBF | 0xCF | 0xDF | 0xEF | 0xFF => op!(zpr, bbs),
+        0x90 => op!(rel, bcc),
+        0x91 => op!(izy, sta),
+        0x92 => op!(izp, sta),
+        0x94 => op!(zpx, sty),
+        0x95 => op!(zpx, sta),
+        0x96 => op!(zpy, stx),
+        0x98 => op!(imp, tya),
+        0x99 => op!(aby, sta),
+        0x9A => op!(imp, txs),
+        0x9C => op!(abs, stz),
+        0x9D => op!(abx, sta),
+        0x9E => op!(abx, stz),
+        0xA0 => op!(imm, ldy),
+        0xA1 => op!(izx, lda),
+        0xA2 => op!(imm, ldx),
+        0xA4 => op!(zp0, ldy),
+        0xA5 => op!(zp0, lda),
+        0xA6 => op!(zp0, ldx),
+        0xA8 => op!(imp, tay),
+        0xA9 => op!(imm, lda),
+        0xAA => op!(imp, tax),
+        0xAC => op!(abs, ldy),
+        0xAD => op!(abs, lda),
+        0xAE => op!(abs, ldx),
+        0xB0 => op!(rel, bcs),
+        0xB1 => op!(izy, lda),
+        0xB2 => op!(izp, lda),
+        0xB4 => op!(zpx, ldy),
+        0xB5 => op!(zpx, lda),
+        0xB6 => op!(zpy, ldx),
+        0xB8 => op!(imp, clv),
+        0xB9 => op!(aby, lda),
+        0xBA => op!(imp, tsx),
+        0xBC => op!(abx, ldy),
+        0xBD => op!(abx, lda),
+        0xBE => op!(aby, ldx),
+        0xC0 => op!(imm, cpy),
+        0xC1 => op!(izx, cmp),
+        0xC4 => op!(zp0, cpy),
+        0xC5 => op!(zp0, cmp),
+        0xC6 => op!(zp0, dec),
+        0xC8 => op!(imp, iny),
+        0xC9 => op!(imm, cmp),
+        0xCA => op!(imp, dex),
+        0xCB => op!(imp, wai),
+        0xCC => op!(abs, cpy),
+        0xCD => op!(abs, cmp),
+        0xCE => op!(abs, dec),
+        0xD0 => op!(rel, bne),
+        0xD1 => op!(izy, cmp),
+        0xD2 => op!(izp, cmp),
+        0xD5 => op!(zpx, cmp),
+        0xD6 => op!(zpx, dec),
+        0xD8 => op!(imp, cld),
+        0xD9 => op!(aby, cmp),
+        0xDA => op!(imp, phx),
+        0xDB => op!(imp, stp),
+        0xDC | 0xFC => op!(abs, nop),
+        0xDD => op!(abx, cmp),
+        0xDE => op!(abx, dec),
+        0xE0 => op!(imm, cpx),
+        0xE1 => op!(izx, sbc),
+        0xE4 => op!(zp0, cpx),
+        0xE5 => op!(zp0, sbc),
+        0xE6 => op!(zp0, inc),
+        0xE8 => op!(imp, inx),
+        0xE9 => op!(imm, sbc),
+        0xEA => op!(imp, nop),
+        0xEC => op!(abs, cpx),
+        0xED => op!(abs, sbc),
+        0xEE => op!(abs, inc),
+        0xF0 => op!(rel, beq),
+        0xF1 => op!(izy, sbc),
+        0xF2 => op!(izp, sbc),
+        0xF5 => op!(zpx, sbc),
+        0xF6 => op!(zpx, inc),
+        0xF8 => op!(imp, sed),
+        0xF9 => op!(aby, sbc),
+        0xFA => op!(imp, plx),
+        0xFD => op!(abx, sbc),
+        0xFE => op!(abx, inc),
+    }
+}
+
 const INSTRUCTIONS: [[Instruction; 16]; 16] = [
     [
         Instruction {
             name: "brk",
-            operate: Cpu::brk,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 7,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "ora",
-            operate: Cpu::ora,
-            addr: Cpu::izx,
             mode: AddrMode::Izx,
             cycles: 6,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "slo",
+            mode: AddrMode::Izx,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Zp0,
             cycles: 3,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "ora",
-            operate: Cpu::ora,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "asl",
-            operate: Cpu::asl,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "slo",
+            mode: AddrMode::Zp0,
             cycles: 5,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "php",
-            operate: Cpu::php,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 3,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "ora",
-            operate: Cpu::ora,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 2,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "asl",
-            operate: Cpu::asl,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "anc",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Abs,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "ora",
-            operate: Cpu::ora,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "asl",
-            operate: Cpu::asl,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "slo",
+            mode: AddrMode::Abs,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
     [
         Instruction {
             name: "bpl",
-            operate: Cpu::bpl,
-            addr: Cpu::rel,
             mode: AddrMode::Rel,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "ora",
-            operate: Cpu::ora,
-            addr: Cpu::izy,
             mode: AddrMode::Izy,
             cycles: 5,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "slo",
+            mode: AddrMode::Izy,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Zpx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "ora",
-            operate: Cpu::ora,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "asl",
-            operate: Cpu::asl,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "slo",
+            mode: AddrMode::Zpx,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "clc",
-            operate: Cpu::clc,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "ora",
-            operate: Cpu::ora,
-            addr: Cpu::aby,
             mode: AddrMode::Aby,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
+            name: "nop",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "slo",
+            mode: AddrMode::Aby,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Abx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "ora",
-            operate: Cpu::ora,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "asl",
-            operate: Cpu::asl,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 7,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "slo",
+            mode: AddrMode::Abx,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
     [
         Instruction {
             name: "jsr",
-            operate: Cpu::jsr,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 6,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "and",
-            operate: Cpu::and,
-            addr: Cpu::izx,
             mode: AddrMode::Izx,
             cycles: 6,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rla",
+            mode: AddrMode::Izx,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "bit",
-            operate: Cpu::bit,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "and",
-            operate: Cpu::and,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "rol",
-            operate: Cpu::rol,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rla",
+            mode: AddrMode::Zp0,
             cycles: 5,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "plp",
-            operate: Cpu::plp,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 4,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "and",
-            operate: Cpu::and,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 2,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "rol",
-            operate: Cpu::rol,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "anc",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "bit",
-            operate: Cpu::bit,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "and",
-            operate: Cpu::and,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "rol",
-            operate: Cpu::rol,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rla",
+            mode: AddrMode::Abs,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
     [
         Instruction {
             name: "bmi",
-            operate: Cpu::bmi,
-            addr: Cpu::rel,
             mode: AddrMode::Rel,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "and",
-            operate: Cpu::and,
-            addr: Cpu::izy,
             mode: AddrMode::Izy,
             cycles: 5,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rla",
+            mode: AddrMode::Izy,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Zpx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "and",
-            operate: Cpu::and,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "rol",
-            operate: Cpu::rol,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rla",
+            mode: AddrMode::Zpx,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "sec",
-            operate: Cpu::sec,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "and",
-            operate: Cpu::and,
-            addr: Cpu::aby,
             mode: AddrMode::Aby,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
+            name: "nop",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rla",
+            mode: AddrMode::Aby,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Abx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "and",
-            operate: Cpu::and,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "rol",
-            operate: Cpu::rol,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 7,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rla",
+            mode: AddrMode::Abx,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
     [
         Instruction {
             name: "rti",
-            operate: Cpu::rti,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 6,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "eor",
-            operate: Cpu::eor,
-            addr: Cpu::izx,
             mode: AddrMode::Izx,
             cycles: 6,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sre",
+            mode: AddrMode::Izx,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Zp0,
             cycles: 3,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "eor",
-            operate: Cpu::eor,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "lsr",
-            operate: Cpu::lsr,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sre",
+            mode: AddrMode::Zp0,
             cycles: 5,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "pha",
-            operate: Cpu::pha,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 3,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "eor",
-            operate: Cpu::eor,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 2,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "lsr",
-            operate: Cpu::lsr,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "alr",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "jmp",
-            operate: Cpu::jmp,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 3,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "eor",
-            operate: Cpu::eor,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "lsr",
-            operate: Cpu::lsr,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sre",
+            mode: AddrMode::Abs,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
     [
         Instruction {
             name: "bvc",
-            operate: Cpu::bvc,
-            addr: Cpu::rel,
             mode: AddrMode::Rel,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "eor",
-            operate: Cpu::eor,
-            addr: Cpu::izy,
             mode: AddrMode::Izy,
             cycles: 5,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sre",
+            mode: AddrMode::Izy,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Zpx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "eor",
-            operate: Cpu::eor,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "lsr",
-            operate: Cpu::lsr,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sre",
+            mode: AddrMode::Zpx,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "cli",
-            operate: Cpu::cli,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "eor",
-            operate: Cpu::eor,
-            addr: Cpu::aby,
             mode: AddrMode::Aby,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
+            name: "nop",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sre",
+            mode: AddrMode::Aby,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Abx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "eor",
-            operate: Cpu::eor,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "lsr",
-            operate: Cpu::lsr,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 7,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sre",
+            mode: AddrMode::Abx,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
     [
         Instruction {
             name: "rts",
-            operate: Cpu::rts,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 6,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "adc",
-            operate: Cpu::adc,
-            addr: Cpu::izx,
             mode: AddrMode::Izx,
             cycles: 6,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rra",
+            mode: AddrMode::Izx,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Zp0,
             cycles: 3,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "adc",
-            operate: Cpu::adc,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "ror",
-            operate: Cpu::ror,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rra",
+            mode: AddrMode::Zp0,
             cycles: 5,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "pla",
-            operate: Cpu::pla,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 4,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "adc",
-            operate: Cpu::adc,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 2,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "ror",
-            operate: Cpu::ror,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "arr",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "jmp",
-            operate: Cpu::jmp,
-            addr: Cpu::ind,
             mode: AddrMode::Ind,
             cycles: 5,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "adc",
-            operate: Cpu::adc,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "ror",
-            operate: Cpu::ror,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rra",
+            mode: AddrMode::Abs,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
     [
         Instruction {
             name: "bvs",
-            operate: Cpu::bvs,
-            addr: Cpu::rel,
             mode: AddrMode::Rel,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "adc",
-            operate: Cpu::adc,
-            addr: Cpu::izy,
             mode: AddrMode::Izy,
             cycles: 5,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rra",
+            mode: AddrMode::Izy,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Zpx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "adc",
-            operate: Cpu::adc,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "ror",
-            operate: Cpu::ror,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rra",
+            mode: AddrMode::Zpx,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "sei",
-            operate: Cpu::sei,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "adc",
-            operate: Cpu::adc,
-            addr: Cpu::aby,
             mode: AddrMode::Aby,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
+            name: "nop",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rra",
+            mode: AddrMode::Aby,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Abx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "adc",
-            operate: Cpu::adc,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "ror",
-            operate: Cpu::ror,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 7,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "rra",
+            mode: AddrMode::Abx,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
     [
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "sta",
-            operate: Cpu::sta,
-            addr: Cpu::izx,
             mode: AddrMode::Izx,
             cycles: 6,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sax",
+            mode: AddrMode::Izx,
             cycles: 6,
+            official: false,
+            access: Access::Write,
         },
         Instruction {
             name: "sty",
-            operate: Cpu::sty,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
             name: "sta",
-            operate: Cpu::sta,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
             name: "stx",
-            operate: Cpu::stx,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sax",
+            mode: AddrMode::Zp0,
             cycles: 3,
+            official: false,
+            access: Access::Write,
         },
         Instruction {
             name: "dey",
-            operate: Cpu::dey,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "txa",
-            operate: Cpu::txa,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "xaa",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "sty",
-            operate: Cpu::sty,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
             name: "sta",
-            operate: Cpu::sta,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
             name: "stx",
-            operate: Cpu::stx,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sax",
+            mode: AddrMode::Abs,
             cycles: 4,
+            official: false,
+            access: Access::Write,
         },
     ],
     [
         Instruction {
             name: "bcc",
-            operate: Cpu::bcc,
-            addr: Cpu::rel,
             mode: AddrMode::Rel,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "sta",
-            operate: Cpu::sta,
-            addr: Cpu::izy,
             mode: AddrMode::Izy,
             cycles: 6,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "ahx",
+            mode: AddrMode::Izy,
             cycles: 6,
+            official: false,
+            access: Access::Write,
         },
         Instruction {
             name: "sty",
-            operate: Cpu::sty,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 4,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
             name: "sta",
-            operate: Cpu::sta,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 4,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
             name: "stx",
-            operate: Cpu::stx,
-            addr: Cpu::zpy,
             mode: AddrMode::Zpy,
             cycles: 4,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sax",
+            mode: AddrMode::Zpy,
             cycles: 4,
+            official: false,
+            access: Access::Write,
         },
         Instruction {
             name: "tya",
-            operate: Cpu::tya,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "sta",
-            operate: Cpu::sta,
-            addr: Cpu::aby,
             mode: AddrMode::Aby,
             cycles: 5,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
             name: "txs",
-            operate: Cpu::txs,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "tas",
+            mode: AddrMode::Aby,
             cycles: 5,
+            official: false,
+            access: Access::Write,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "shy",
+            mode: AddrMode::Abx,
             cycles: 5,
+            official: false,
+            access: Access::Write,
         },
         Instruction {
             name: "sta",
-            operate: Cpu::sta,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 5,
+            official: true,
+            access: Access::Write,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "shx",
+            mode: AddrMode::Aby,
             cycles: 5,
+            official: false,
+            access: Access::Write,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "ahx",
+            mode: AddrMode::Aby,
             cycles: 5,
+            official: false,
+            access: Access::Write,
         },
     ],
     [
         Instruction {
             name: "ldy",
-            operate: Cpu::ldy,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 2,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "lda",
-            operate: Cpu::lda,
-            addr: Cpu::izx,
             mode: AddrMode::Izx,
             cycles: 6,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "ldx",
-            operate: Cpu::ldx,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 2,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "lax",
+            mode: AddrMode::Izx,
             cycles: 6,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "ldy",
-            operate: Cpu::ldy,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "lda",
-            operate: Cpu::lda,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "ldx",
-            operate: Cpu::ldx,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "lax",
+            mode: AddrMode::Zp0,
             cycles: 3,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "tay",
-            operate: Cpu::tay,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "lda",
-            operate: Cpu::lda,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 2,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "tax",
-            operate: Cpu::tax,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "lxa",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "ldy",
-            operate: Cpu::ldy,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "lda",
-            operate: Cpu::lda,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "ldx",
-            operate: Cpu::ldx,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "lax",
+            mode: AddrMode::Abs,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
     ],
     [
         Instruction {
             name: "bcs",
-            operate: Cpu::bcs,
-            addr: Cpu::rel,
             mode: AddrMode::Rel,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "lda",
-            operate: Cpu::lda,
-            addr: Cpu::izy,
             mode: AddrMode::Izy,
             cycles: 5,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "lax",
+            mode: AddrMode::Izy,
             cycles: 5,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "ldy",
-            operate: Cpu::ldy,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "lda",
-            operate: Cpu::lda,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "ldx",
-            operate: Cpu::ldx,
-            addr: Cpu::zpy,
             mode: AddrMode::Zpy,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "lax",
+            mode: AddrMode::Zpy,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "clv",
-            operate: Cpu::clv,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "lda",
-            operate: Cpu::lda,
-            addr: Cpu::aby,
             mode: AddrMode::Aby,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "tsx",
-            operate: Cpu::tsx,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "las",
+            mode: AddrMode::Aby,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "ldy",
-            operate: Cpu::ldy,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "lda",
-            operate: Cpu::lda,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "ldx",
-            operate: Cpu::ldx,
-            addr: Cpu::aby,
             mode: AddrMode::Aby,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "lax",
+            mode: AddrMode::Aby,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
     ],
     [
         Instruction {
             name: "cpy",
-            operate: Cpu::cpy,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 2,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "cmp",
-            operate: Cpu::cmp,
-            addr: Cpu::izx,
             mode: AddrMode::Izx,
             cycles: 6,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "dcp",
+            mode: AddrMode::Izx,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "cpy",
-            operate: Cpu::cpy,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "cmp",
-            operate: Cpu::cmp,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "dec",
-            operate: Cpu::dec,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "dcp",
+            mode: AddrMode::Zp0,
             cycles: 5,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "iny",
-            operate: Cpu::iny,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "cmp",
-            operate: Cpu::cmp,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 2,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "dex",
-            operate: Cpu::dex,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "axs",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "cpy",
-            operate: Cpu::cpy,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "cmp",
-            operate: Cpu::cmp,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "dec",
-            operate: Cpu::dec,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "dcp",
+            mode: AddrMode::Abs,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
     [
         Instruction {
             name: "bne",
-            operate: Cpu::bne,
-            addr: Cpu::rel,
             mode: AddrMode::Rel,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "cmp",
-            operate: Cpu::cmp,
-            addr: Cpu::izy,
             mode: AddrMode::Izy,
             cycles: 5,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "dcp",
+            mode: AddrMode::Izy,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Zpx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "cmp",
-            operate: Cpu::cmp,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "dec",
-            operate: Cpu::dec,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "dcp",
+            mode: AddrMode::Zpx,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "cld",
-            operate: Cpu::cld,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "cmp",
-            operate: Cpu::cmp,
-            addr: Cpu::aby,
             mode: AddrMode::Aby,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "nop",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "dcp",
+            mode: AddrMode::Aby,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Abx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "cmp",
-            operate: Cpu::cmp,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "dec",
-            operate: Cpu::dec,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 7,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "dcp",
+            mode: AddrMode::Abx,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
     [
         Instruction {
             name: "cpx",
-            operate: Cpu::cpx,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 2,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "sbc",
-            operate: Cpu::sbc,
-            addr: Cpu::izx,
             mode: AddrMode::Izx,
             cycles: 6,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "isc",
+            mode: AddrMode::Izx,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "cpx",
-            operate: Cpu::cpx,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "sbc",
-            operate: Cpu::sbc,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 3,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "inc",
-            operate: Cpu::inc,
-            addr: Cpu::zp0,
             mode: AddrMode::Zp0,
             cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "isc",
+            mode: AddrMode::Zp0,
             cycles: 5,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "inx",
-            operate: Cpu::inx,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "sbc",
-            operate: Cpu::sbc,
-            addr: Cpu::imm,
             mode: AddrMode::Imm,
             cycles: 2,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "nop",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::sbc,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "sbc",
+            mode: AddrMode::Imm,
             cycles: 2,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "cpx",
-            operate: Cpu::cpx,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "sbc",
-            operate: Cpu::sbc,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "inc",
-            operate: Cpu::inc,
-            addr: Cpu::abs,
             mode: AddrMode::Abs,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "isc",
+            mode: AddrMode::Abs,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
     [
         Instruction {
             name: "beq",
-            operate: Cpu::beq,
-            addr: Cpu::rel,
             mode: AddrMode::Rel,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "sbc",
-            operate: Cpu::sbc,
-            addr: Cpu::izy,
             mode: AddrMode::Izy,
             cycles: 5,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
+            name: "jam",
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "isc",
+            mode: AddrMode::Izy,
             cycles: 8,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Zpx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "sbc",
-            operate: Cpu::sbc,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "inc",
-            operate: Cpu::inc,
-            addr: Cpu::zpx,
             mode: AddrMode::Zpx,
             cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "isc",
+            mode: AddrMode::Zpx,
             cycles: 6,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
             name: "sed",
-            operate: Cpu::sed,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: true,
+            access: Access::None,
         },
         Instruction {
             name: "sbc",
-            operate: Cpu::sbc,
-            addr: Cpu::aby,
             mode: AddrMode::Aby,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "nop",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
             mode: AddrMode::Imp,
             cycles: 2,
+            official: false,
+            access: Access::None,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "isc",
+            mode: AddrMode::Aby,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::nop,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "nop",
+            mode: AddrMode::Abx,
             cycles: 4,
+            official: false,
+            access: Access::Read,
         },
         Instruction {
             name: "sbc",
-            operate: Cpu::sbc,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 4,
+            official: true,
+            access: Access::Read,
         },
         Instruction {
             name: "inc",
-            operate: Cpu::inc,
-            addr: Cpu::abx,
             mode: AddrMode::Abx,
             cycles: 7,
+            official: true,
+            access: Access::ReadModifyWrite,
         },
         Instruction {
-            name: "???",
-            operate: Cpu::xxx,
-            addr: Cpu::imp,
-            mode: AddrMode::Imp,
+            name: "isc",
+            mode: AddrMode::Abx,
             cycles: 7,
+            official: false,
+            access: Access::ReadModifyWrite,
         },
     ],
 ];
+
+// The 65C02 keeps the official NMOS opcodes and reuses the unofficial ones for new instructions,
+// the remaining slots are nops of various lengths
+const INSTRUCTIONS_65C02: [[Instruction; 16]; 16] = [
+    [
+        Instruction {
+            name: "brk",
+            mode: AddrMode::Imm,
+            cycles: 7,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "ora",
+            mode: AddrMode::Izx,
+            cycles: 6,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "tsb",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "ora",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "asl",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "rmb0",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "php",
+            mode: AddrMode::Imp,
+            cycles: 3,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "ora",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "asl",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "tsb",
+            mode: AddrMode::Abs,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "ora",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "asl",
+            mode: AddrMode::Abs,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbr0",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "bpl",
+            mode: AddrMode::Rel,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "ora",
+            mode: AddrMode::Izy,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ora",
+            mode: AddrMode::Izp,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "trb",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "ora",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "asl",
+            mode: AddrMode::Zpx,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "rmb1",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "clc",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "ora",
+            mode: AddrMode::Aby,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "inc",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "trb",
+            mode: AddrMode::Abs,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "ora",
+            mode: AddrMode::Abx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "asl",
+            mode: AddrMode::Abx,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbr1",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "jsr",
+            mode: AddrMode::Abs,
+            cycles: 6,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "and",
+            mode: AddrMode::Izx,
+            cycles: 6,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "bit",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "and",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "rol",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "rmb2",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "plp",
+            mode: AddrMode::Imp,
+            cycles: 4,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "and",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "rol",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "bit",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "and",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "rol",
+            mode: AddrMode::Abs,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbr2",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "bmi",
+            mode: AddrMode::Rel,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "and",
+            mode: AddrMode::Izy,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "and",
+            mode: AddrMode::Izp,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "bit",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "and",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "rol",
+            mode: AddrMode::Zpx,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "rmb3",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "sec",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "and",
+            mode: AddrMode::Aby,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "dec",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "bit",
+            mode: AddrMode::Abx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "and",
+            mode: AddrMode::Abx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "rol",
+            mode: AddrMode::Abx,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbr3",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "rti",
+            mode: AddrMode::Imp,
+            cycles: 6,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "eor",
+            mode: AddrMode::Izx,
+            cycles: 6,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "eor",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "lsr",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "rmb4",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "pha",
+            mode: AddrMode::Imp,
+            cycles: 3,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "eor",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "lsr",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "jmp",
+            mode: AddrMode::Abs,
+            cycles: 3,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "eor",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "lsr",
+            mode: AddrMode::Abs,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbr4",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "bvc",
+            mode: AddrMode::Rel,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "eor",
+            mode: AddrMode::Izy,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "eor",
+            mode: AddrMode::Izp,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "eor",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "lsr",
+            mode: AddrMode::Zpx,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "rmb5",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "cli",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "eor",
+            mode: AddrMode::Aby,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "phy",
+            mode: AddrMode::Imp,
+            cycles: 3,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Abs,
+            cycles: 8,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "eor",
+            mode: AddrMode::Abx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "lsr",
+            mode: AddrMode::Abx,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbr5",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "rts",
+            mode: AddrMode::Imp,
+            cycles: 6,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "adc",
+            mode: AddrMode::Izx,
+            cycles: 6,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "stz",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "adc",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ror",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "rmb6",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "pla",
+            mode: AddrMode::Imp,
+            cycles: 4,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "adc",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ror",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "jmp",
+            mode: AddrMode::Ind,
+            cycles: 6,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "adc",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ror",
+            mode: AddrMode::Abs,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbr6",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "bvs",
+            mode: AddrMode::Rel,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "adc",
+            mode: AddrMode::Izy,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "adc",
+            mode: AddrMode::Izp,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "stz",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "adc",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ror",
+            mode: AddrMode::Zpx,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "rmb7",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "sei",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "adc",
+            mode: AddrMode::Aby,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ply",
+            mode: AddrMode::Imp,
+            cycles: 4,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "jmp",
+            mode: AddrMode::Iax,
+            cycles: 6,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "adc",
+            mode: AddrMode::Abx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ror",
+            mode: AddrMode::Abx,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbr7",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "bra",
+            mode: AddrMode::Rel,
+            cycles: 3,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "sta",
+            mode: AddrMode::Izx,
+            cycles: 6,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "sty",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "sta",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "stx",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "smb0",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "dey",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "bit",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "txa",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "sty",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "sta",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "stx",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "bbs0",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "bcc",
+            mode: AddrMode::Rel,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "sta",
+            mode: AddrMode::Izy,
+            cycles: 6,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "sta",
+            mode: AddrMode::Izp,
+            cycles: 5,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "sty",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "sta",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "stx",
+            mode: AddrMode::Zpy,
+            cycles: 4,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "smb1",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "tya",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "sta",
+            mode: AddrMode::Aby,
+            cycles: 5,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "txs",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "stz",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "sta",
+            mode: AddrMode::Abx,
+            cycles: 5,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "stz",
+            mode: AddrMode::Abx,
+            cycles: 5,
+            official: true,
+            access: Access::Write,
+        },
+        Instruction {
+            name: "bbs1",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "ldy",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "lda",
+            mode: AddrMode::Izx,
+            cycles: 6,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ldx",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "ldy",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "lda",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ldx",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "smb2",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "tay",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "lda",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "tax",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "ldy",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "lda",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ldx",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "bbs2",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "bcs",
+            mode: AddrMode::Rel,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "lda",
+            mode: AddrMode::Izy,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "lda",
+            mode: AddrMode::Izp,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "ldy",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "lda",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ldx",
+            mode: AddrMode::Zpy,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "smb3",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "clv",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "lda",
+            mode: AddrMode::Aby,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "tsx",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "ldy",
+            mode: AddrMode::Abx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "lda",
+            mode: AddrMode::Abx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "ldx",
+            mode: AddrMode::Aby,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "bbs3",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "cpy",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "cmp",
+            mode: AddrMode::Izx,
+            cycles: 6,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "cpy",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "cmp",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "dec",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "smb4",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "iny",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "cmp",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "dex",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "wai",
+            mode: AddrMode::Imp,
+            cycles: 3,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "cpy",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "cmp",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "dec",
+            mode: AddrMode::Abs,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbs4",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "bne",
+            mode: AddrMode::Rel,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "cmp",
+            mode: AddrMode::Izy,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "cmp",
+            mode: AddrMode::Izp,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "cmp",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "dec",
+            mode: AddrMode::Zpx,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "smb5",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "cld",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "cmp",
+            mode: AddrMode::Aby,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "phx",
+            mode: AddrMode::Imp,
+            cycles: 3,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "stp",
+            mode: AddrMode::Imp,
+            cycles: 3,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "cmp",
+            mode: AddrMode::Abx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "dec",
+            mode: AddrMode::Abx,
+            cycles: 7,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbs5",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "cpx",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "sbc",
+            mode: AddrMode::Izx,
+            cycles: 6,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "cpx",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "sbc",
+            mode: AddrMode::Zp0,
+            cycles: 3,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "inc",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "smb6",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "inx",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "sbc",
+            mode: AddrMode::Imm,
+            cycles: 2,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "cpx",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "sbc",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "inc",
+            mode: AddrMode::Abs,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbs6",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+    [
+        Instruction {
+            name: "beq",
+            mode: AddrMode::Rel,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "sbc",
+            mode: AddrMode::Izy,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "sbc",
+            mode: AddrMode::Izp,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "sbc",
+            mode: AddrMode::Zpx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "inc",
+            mode: AddrMode::Zpx,
+            cycles: 6,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "smb7",
+            mode: AddrMode::Zp0,
+            cycles: 5,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "sed",
+            mode: AddrMode::Imp,
+            cycles: 2,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "sbc",
+            mode: AddrMode::Aby,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "plx",
+            mode: AddrMode::Imp,
+            cycles: 4,
+            official: true,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Imp,
+            cycles: 1,
+            official: false,
+            access: Access::None,
+        },
+        Instruction {
+            name: "nop",
+            mode: AddrMode::Abs,
+            cycles: 4,
+            official: false,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "sbc",
+            mode: AddrMode::Abx,
+            cycles: 4,
+            official: true,
+            access: Access::Read,
+        },
+        Instruction {
+            name: "inc",
+            mode: AddrMode::Abx,
+            cycles: 7,
+            official: true,
+            access: Access::ReadModifyWrite,
+        },
+        Instruction {
+            name: "bbs7",
+            mode: AddrMode::Zpr,
+            cycles: 5,
+            official: true,
+            access: Access::Read,
+        },
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nes::bus::FlatBus;
+    use crate::nes::cpu::{CpuState, StatusFlags};
+
+    const ORIGIN: u16 = 0x0200;
+
+    // Runs every opcode once and checks the dispatch against the table: the cycles taken and the
+    // bytes skipped must match the base cycles and the length of the addressing mode. The operand
+    // is $0300, or $00 for the zero page modes, and every pointer in memory is zero, so no page is
+    // crossed. Branches have an offset of 0 so they fall through, taken or not.
+    #[test]
+    fn dispatch_matches_table() {
+        for variant in [Variant::Ricoh2A03, Variant::Nmos6502, Variant::Wdc65C02] {
+            for opcode in 0..=0xFFu8 {
+                let instruction = get_variant_instruction(variant, opcode);
+
+                if matches!(instruction.name, "jam" | "stp") {
+                    continue;
+                }
+
+                let mut bus = FlatBus::default();
+                bus.load(ORIGIN, &[opcode, 0x00, 0x03]);
+
+                let mut cpu = Cpu::new(variant);
+                cpu.set_state(&CpuState {
+                    a: 0,
+                    x: 0,
+                    y: 0,
+                    sp: 0xFD,
+                    pc: ORIGIN,
+                    p: StatusFlags::UNUSED | StatusFlags::INTERRUPT_DISABLE,
+                    opcode: 0,
+                    cycles: 0,
+                });
+
+                let cycles = cpu.step(&mut bus).unwrap();
+                let len = cpu.get_state().pc.wrapping_sub(ORIGIN);
+
+                let branch = matches!(instruction.mode, AddrMode::Rel | AddrMode::Zpr);
+                let expected = if branch {
+                    // A taken branch spends one more cycle
+                    instruction.cycles as u16..=instruction.cycles as u16 + 1
+                } else {
+                    instruction.cycles as u16..=instruction.cycles as u16
+                };
+
+                assert!(
+                    expected.contains(&cycles),
+                    "{:?} ${:02X} {}: {} cycles, the table has {}",
+                    variant,
+                    opcode,
+                    instruction.name,
+                    cycles,
+                    instruction.cycles
+                );
+
+                // Jumps, returns and bit branches leave pc elsewhere
+                if !matches!(instruction.name, "jmp" | "jsr" | "rts" | "rti" | "brk")
+                    && instruction.mode != AddrMode::Zpr
+                {
+                    assert_eq!(
+                        len,
+                        instruction.byte_len(),
+                        "{:?} ${:02X} {}: pc moved {} bytes, the table has {}",
+                        variant,
+                        opcode,
+                        instruction.name,
+                        len,
+                        instruction.byte_len()
+                    );
+                }
+            }
+        }
+    }
+}
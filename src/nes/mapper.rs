@@ -1,4 +1,4 @@
-use super::bus::ADDR_PRG_ROM;
+use super::bus::{ADDR_PRG_RAM, ADDR_PRG_ROM};
 use super::cartridge::{Cartridge, CartridgeState};
 
 pub trait Mapper {
@@ -7,6 +7,14 @@ pub trait Mapper {
 
     fn ppu_read(&self, addr: usize, cart: &Cartridge) -> u8;
     fn ppu_write(&mut self, addr: usize, data: u8, cart: &mut CartridgeState);
+
+    // Called once per CPU cycle, for mappers counting cycles
+    fn tick(&mut self, _cart: &mut CartridgeState) {}
+
+    // Level of the mapper IRQ
+    fn irq(&self) -> bool {
+        false
+    }
 }
 
 pub enum MapperKind {
@@ -46,12 +54,24 @@ impl Mapper for MapperKind {
     fn ppu_write(&mut self, addr: usize, data: u8, cart: &mut CartridgeState) {
         delegate_mapper!(self, ppu_write, addr, data, cart)
     }
+
+    fn tick(&mut self, cart: &mut CartridgeState) {
+        delegate_mapper!(self, tick, cart)
+    }
+
+    fn irq(&self) -> bool {
+        delegate_mapper!(self, irq)
+    }
 }
 
 pub struct NromMapper {}
 
 impl Mapper for NromMapper {
     fn cpu_read(&self, addr: usize, cart: &Cartridge) -> u8 {
+        if addr < ADDR_PRG_ROM {
+            return prg_ram_read(addr, &cart.state);
+        }
+
         let offset = addr - ADDR_PRG_ROM;
         let mapped_addr = if cart.nb_prg_banks == 1 {
             offset & 0x3FFF
@@ -62,15 +82,41 @@ impl Mapper for NromMapper {
         cart.prg_rom.get(mapped_addr).copied().unwrap_or(0)
     }
 
+    // Only PRG RAM can be written, there are no registers
     fn cpu_write(&mut self, addr: usize, data: u8, cart: &mut CartridgeState) {
-        todo!()
+        if addr < ADDR_PRG_ROM {
+            prg_ram_write(addr, data, cart);
+        }
     }
 
     fn ppu_read(&self, addr: usize, cart: &Cartridge) -> u8 {
-        todo!()
+        if cart.chr_rom.is_empty() {
+            cart.state.chr_ram.get(addr).copied().unwrap_or(0)
+        } else {
+            cart.chr_rom.get(addr).copied().unwrap_or(0)
+        }
     }
 
+    // Only CHR RAM can be written
     fn ppu_write(&mut self, addr: usize, data: u8, cart: &mut CartridgeState) {
-        todo!()
+        if let Some(byte) = cart.chr_ram.get_mut(addr) {
+            *byte = data;
+        }
+    }
+}
+
+// PRG RAM at $6000-$7FFF, mirrored when smaller than 8 KiB. Open bus without one.
+fn prg_ram_read(addr: usize, cart: &CartridgeState) -> u8 {
+    if cart.prg_ram.is_empty() {
+        return 0;
+    }
+
+    cart.prg_ram[(addr - ADDR_PRG_RAM) % cart.prg_ram.len()]
+}
+
+fn prg_ram_write(addr: usize, data: u8, cart: &mut CartridgeState) {
+    if !cart.prg_ram.is_empty() {
+        let len = cart.prg_ram.len();
+        cart.prg_ram[(addr - ADDR_PRG_RAM) % len] = data;
     }
 }
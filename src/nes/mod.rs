@@ -1,48 +1,103 @@
 use bus::Bus;
 use cartridge::Cartridge;
-use cpu::Cpu;
-use ppu::Ppu;
-use std::cell::RefCell;
+use cpu::{Cpu, Jam};
+use image::Image;
+use palette::Palette;
+use ppu::{SCREEN_HEIGHT, SCREEN_WIDTH};
 use std::io;
 use std::path::Path;
-use std::rc::Rc;
 
+pub mod apu;
+pub mod assembler;
 pub mod bus;
 pub mod cartridge;
 pub mod cpu;
+pub mod disassembler;
+pub mod image;
 pub mod instructions;
 pub mod mapper;
+pub mod palette;
 pub mod ppu;
 
+// TV system the console was built for. Both run from a master clock divided down for the CPU and the
+// PPU: 3 PPU dots per CPU cycle on NTSC, 3.2 on PAL.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Region {
+    #[default]
+    Ntsc,
+    Pal,
+}
+
+impl Region {
+    // Master clock cycles per CPU cycle
+    pub fn cpu_divider(self) -> u64 {
+        match self {
+            Region::Ntsc => 12,
+            Region::Pal => 16,
+        }
+    }
+
+    // Master clock cycles per PPU dot
+    pub fn ppu_divider(self) -> u64 {
+        match self {
+            Region::Ntsc => 4,
+            Region::Pal => 5,
+        }
+    }
+
+    pub fn scanlines_per_frame(self) -> u16 {
+        match self {
+            Region::Ntsc => 262,
+            Region::Pal => 312,
+        }
+    }
+}
+
+// Rows at the top and the bottom of the frame most NTSC TVs hide
+pub const OVERSCAN: usize = 8;
+
+pub struct ScreenshotOptions {
+    pub crop_overscan: bool, // Leaves out the rows hidden by the overscan, 256x224
+    pub scale: usize,        // Integer upscaling factor, 0 is treated as 1
+}
+
+impl Default for ScreenshotOptions {
+    fn default() -> Self {
+        Self {
+            crop_overscan: false,
+            scale: 1,
+        }
+    }
+}
+
 pub struct Nes {
     pub cpu: Cpu,
     pub bus: Bus,
-    ppu: Rc<RefCell<Ppu>>,
-    cartridge: Rc<RefCell<Cartridge>>,
 }
 
+// Every device has a single owner so a Nes can be moved to another thread
+const _: fn() = || {
+    fn assert_send<T: Send>() {}
+    assert_send::<Nes>();
+};
+
 impl Nes {
     pub fn from_rom(path: impl AsRef<Path>) -> Result<Self, io::Error> {
-        let cartridge = Rc::new(RefCell::new(Cartridge::from_rom(path)?));
-        let ppu = Rc::new(RefCell::new(Ppu::default()));
+        let cartridge = Cartridge::from_rom(path)?;
+        let region = cartridge.region;
 
         Ok(Self {
             cpu: Cpu::default(),
-            bus: Bus::new(Rc::clone(&ppu), Rc::clone(&cartridge)),
-            ppu,
-            cartridge,
+            bus: Bus::new(cartridge, region),
         })
     }
 
     pub fn from_program(program: &str) -> Result<Self, String> {
-        let cartridge = Rc::new(RefCell::new(Cartridge::from_program(program)?));
-        let ppu = Rc::new(RefCell::new(Ppu::default()));
+        let cartridge = Cartridge::from_program(program)?;
 
         Ok(Self {
             cpu: Cpu::default(),
-            bus: Bus::new(Rc::clone(&ppu), Rc::clone(&cartridge)),
-            ppu,
-            cartridge,
+            bus: Bus::new(cartridge, Region::Ntsc),
         })
     }
 
@@ -50,7 +105,63 @@ impl Nes {
         self.cpu.reset(&mut self.bus);
     }
 
-    pub fn run(&mut self) {
-        println!("Run");
+    pub fn region(&self) -> Region {
+        self.bus.region()
+    }
+
+    // Runs frame after frame until the CPU executes a jam opcode
+    pub fn run(&mut self) -> Result<(), Jam> {
+        loop {
+            self.run_frame()?;
+        }
+    }
+
+    // Runs until the PPU completes a frame, it is then ready in the framebuffer. Returns the CPU
+    // cycles elapsed, the instruction running when the frame completes is finished first.
+    pub fn run_frame(&mut self) -> Result<u64, Jam> {
+        let start = self.cpu.total_cycles();
+        let frame = self.bus.ppu.frame_count();
+
+        while self.bus.ppu.frame_count() == frame {
+            self.cpu.step(&mut self.bus)?;
+        }
+
+        Ok(self.cpu.total_cycles() - start)
+    }
+
+    // Last completed frame converted with the palette
+    pub fn screenshot(&self, palette: &Palette, options: &ScreenshotOptions) -> Image {
+        let mut image = Image::from_frame(self.bus.ppu.frame(), SCREEN_WIDTH, palette);
+
+        if options.crop_overscan {
+            image = image.crop(0, OVERSCAN, SCREEN_WIDTH, SCREEN_HEIGHT - OVERSCAN * 2);
+        }
+
+        if options.scale > 1 {
+            image = image.scale(options.scale);
+        }
+
+        image
+    }
+
+    pub fn save_screenshot(
+        &self,
+        path: impl AsRef<Path>,
+        palette: &Palette,
+        options: &ScreenshotOptions,
+    ) -> Result<(), io::Error> {
+        self.screenshot(palette, options).save_png(path)
+    }
+
+    pub fn run_cycles(&mut self, cycles: u64) -> Result<u64, Jam> {
+        self.cpu.run_cycles(&mut self.bus, cycles)
+    }
+
+    pub fn run_until_pc(&mut self, pc: u16) -> Result<u64, Jam> {
+        self.cpu.run_until_pc(&mut self.bus, pc)
+    }
+
+    pub fn run_instructions(&mut self, count: u64) -> Result<u64, Jam> {
+        self.cpu.run_instructions(&mut self.bus, count)
     }
 }
@@ -0,0 +1,144 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// 64 palette entries, each under the 8 combinations of the emphasis bits
+const COLORS: usize = 64;
+const EMPHASIS_COMBINATIONS: usize = 8;
+
+// Emphasizing a channel darkens the other two by about 18%
+const EMPHASIS_ATTENUATION: f32 = 0.816;
+
+// Built-in colours of the NTSC 2C02
+#[rustfmt::skip]
+const NTSC_COLORS: [[u8; 3]; COLORS] = [
+    [84, 84, 84],    [0, 30, 116],    [8, 16, 144],    [48, 0, 136],
+    [68, 0, 100],    [92, 0, 48],     [84, 4, 0],      [60, 24, 0],
+    [32, 42, 0],     [8, 58, 0],      [0, 64, 0],      [0, 60, 0],
+    [0, 50, 60],     [0, 0, 0],       [0, 0, 0],       [0, 0, 0],
+    [152, 150, 152], [8, 76, 196],    [48, 50, 236],   [92, 30, 228],
+    [136, 20, 176],  [160, 20, 100],  [152, 34, 32],   [120, 60, 0],
+    [84, 90, 0],     [40, 114, 0],    [8, 124, 0],     [0, 118, 40],
+    [0, 102, 120],   [0, 0, 0],       [0, 0, 0],       [0, 0, 0],
+    [236, 238, 236], [76, 154, 236],  [120, 124, 236], [176, 98, 236],
+    [228, 84, 236],  [236, 88, 180],  [236, 106, 100], [212, 136, 32],
+    [160, 170, 0],   [116, 196, 0],   [76, 208, 32],   [56, 204, 108],
+    [56, 180, 204],  [60, 60, 60],    [0, 0, 0],       [0, 0, 0],
+    [236, 238, 236], [168, 204, 236], [188, 188, 236], [212, 178, 236],
+    [236, 174, 236], [236, 174, 212], [236, 180, 176], [228, 196, 144],
+    [204, 210, 120], [180, 222, 120], [168, 226, 144], [152, 226, 180],
+    [160, 214, 228], [160, 162, 160], [0, 0, 0],       [0, 0, 0],
+];
+
+// RGB colour of every PPU output value: the palette entry in the low 6 bits and the emphasis
+// bits of PPUMASK (red, green, blue) above them, see Ppu::frame
+pub struct Palette {
+    colors: [[u8; 3]; COLORS * EMPHASIS_COMBINATIONS],
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::from_colors(&NTSC_COLORS)
+    }
+}
+
+impl Palette {
+    // Derives the emphasized colours from the 64 base ones
+    fn from_colors(base: &[[u8; 3]]) -> Self {
+        let mut colors = [[0; 3]; COLORS * EMPHASIS_COMBINATIONS];
+
+        for (emphasis, block) in colors.chunks_exact_mut(COLORS).enumerate() {
+            for (color, rgb) in block.iter_mut().zip(base) {
+                for (channel, (value, &base)) in color.iter_mut().zip(rgb).enumerate() {
+                    // A channel is dimmed when any other channel is emphasized
+                    let dimmed = emphasis & !(1 << channel) != 0;
+
+                    *value = if dimmed {
+                        (base as f32 * EMPHASIS_ATTENUATION).round() as u8
+                    } else {
+                        base
+                    };
+                }
+            }
+        }
+
+        Self { colors }
+    }
+
+    // Loads a .pal file: 64 RGB triplets, or 512 with the emphasized colours precomputed
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, io::Error> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, io::Error> {
+        let triplets: Vec<[u8; 3]> = data
+            .chunks_exact(3)
+            .map(|rgb| [rgb[0], rgb[1], rgb[2]])
+            .collect();
+
+        match data.len() {
+            192 => Ok(Self::from_colors(&triplets)),
+            1536 => {
+                let mut colors = [[0; 3]; COLORS * EMPHASIS_COMBINATIONS];
+                colors.copy_from_slice(&triplets);
+
+                Ok(Self { colors })
+            }
+            len => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid palette size: {} bytes (192 or 1536)", len),
+            )),
+        }
+    }
+
+    pub fn rgb(&self, color: u16) -> [u8; 3] {
+        self.colors[color as usize % self.colors.len()]
+    }
+
+    // Converts a frame of PPU output values to packed RGB bytes
+    pub fn to_rgb(&self, frame: &[u16]) -> Vec<u8> {
+        frame.iter().flat_map(|&color| self.rgb(color)).collect()
+    }
+
+    // Same as to_rgb with an opaque alpha channel
+    pub fn to_rgba(&self, frame: &[u16]) -> Vec<u8> {
+        frame
+            .iter()
+            .flat_map(|&color| {
+                let [r, g, b] = self.rgb(color);
+                [r, g, b, 0xFF]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Colour 0x16 is [152, 34, 32], 0.816 of it is [124, 28, 26]
+    const COLOR: u16 = 0x16;
+
+    fn emphasized(emphasis: u16) -> [u8; 3] {
+        Palette::default().rgb(emphasis << 6 | COLOR)
+    }
+
+    #[test]
+    fn no_emphasis() {
+        assert_eq!(emphasized(0), [152, 34, 32]);
+    }
+
+    #[test]
+    fn single_emphasis() {
+        assert_eq!(emphasized(1), [152, 28, 26]);
+        assert_eq!(emphasized(2), [124, 34, 26]);
+        assert_eq!(emphasized(4), [124, 28, 32]);
+    }
+
+    #[test]
+    fn multiple_emphasis() {
+        // Red and green dim each other, blue is dimmed by both
+        assert_eq!(emphasized(3), [124, 28, 26]);
+        assert_eq!(emphasized(7), [124, 28, 26]);
+    }
+}
@@ -1,17 +1,1133 @@
-pub struct Ppu {
-    pub pattern: [u8; 8 * 1024],
-    pub name_table: [u8; 2 * 1024],
-    pub palette: [u8; 32],
-    pub registers: [u8; 8],
-}
-
-impl Default for Ppu {
-    fn default() -> Self {
-        Self {
-            pattern: [0; 8 * 1024],
-            name_table: [0; 2 * 1024],
-            palette: [0; 32],
-            registers: [0; 8],
-        }
-    }
-}
+use super::Region;
+use super::cartridge::{Cartridge, Mirroring};
+
+// Bits of PPUCTRL ($2000)
+pub struct PpuCtrl;
+
+impl PpuCtrl {
+    pub const NAMETABLE: u8 = 0b0000_0011; // Base nametable, copied into t
+    pub const INCREMENT_32: u8 = 0b0000_0100; // PPUDATA moves down a row instead of across
+    pub const SPRITE_TABLE: u8 = 0b0000_1000;
+    pub const BACKGROUND_TABLE: u8 = 0b0001_0000;
+    pub const SPRITE_SIZE: u8 = 0b0010_0000; // 8x16 sprites
+    pub const MASTER_SLAVE: u8 = 0b0100_0000;
+    pub const NMI_ENABLE: u8 = 0b1000_0000;
+}
+
+// Bits of PPUMASK ($2001)
+pub struct PpuMask;
+
+impl PpuMask {
+    pub const GREYSCALE: u8 = 0b0000_0001;
+    pub const SHOW_BACKGROUND_LEFT: u8 = 0b0000_0010;
+    pub const SHOW_SPRITES_LEFT: u8 = 0b0000_0100;
+    pub const SHOW_BACKGROUND: u8 = 0b0000_1000;
+    pub const SHOW_SPRITES: u8 = 0b0001_0000;
+    pub const EMPHASIZE_RED: u8 = 0b0010_0000;
+    pub const EMPHASIZE_GREEN: u8 = 0b0100_0000;
+    pub const EMPHASIZE_BLUE: u8 = 0b1000_0000;
+    pub const EMPHASIS: u8 = 0b1110_0000;
+}
+
+// Bits of PPUSTATUS ($2002), the low 5 bits read back the I/O latch
+pub struct PpuStatus;
+
+impl PpuStatus {
+    pub const SPRITE_OVERFLOW: u8 = 0b0010_0000;
+    pub const SPRITE_ZERO_HIT: u8 = 0b0100_0000;
+    pub const VBLANK: u8 = 0b1000_0000;
+}
+
+// Registers, selected by the low 3 bits of the CPU address in $2000-$3FFF
+const PPUCTRL: u16 = 0;
+const PPUMASK: u16 = 1;
+const PPUSTATUS: u16 = 2;
+const OAMADDR: u16 = 3;
+pub const OAMDATA: u16 = 4;
+const PPUSCROLL: u16 = 5;
+const PPUADDR: u16 = 6;
+const PPUDATA: u16 = 7;
+
+const ADDR_NAME_TABLE: u16 = 0x2000;
+const ADDR_ATTRIBUTE_TABLE: u16 = 0x23C0;
+pub const ADDR_PALETTE: u16 = 0x3F00;
+
+const NAME_TABLE_SIZE: u16 = 0x0400;
+
+const SPRITES_PER_SCANLINE: usize = 8;
+
+pub const SCREEN_WIDTH: usize = 256;
+pub const SCREEN_HEIGHT: usize = 240;
+
+// Scanlines 0-239 are visible, VBlank starts after the post-render scanline and the last scanline
+// of the frame, the pre-render one, fetches the first tiles of the next frame. PAL has a longer
+// VBlank.
+pub const DOTS_PER_SCANLINE: u16 = 341;
+pub const POST_RENDER_SCANLINE: u16 = 240;
+pub const VBLANK_SCANLINE: u16 = 241;
+
+pub struct Ppu {
+    region: Region,
+
+    // The console has 2 KiB of VRAM for nametables, four-screen cartridges add the other 2 KiB
+    pub name_table: [u8; 4 * 1024],
+    pub palette: [u8; 32],
+    pub oam: [u8; 256], // Object attribute memory, 4 bytes per sprite
+
+    ctrl: u8,
+    mask: u8,
+    status: u8,
+    oam_addr: u8,
+
+    // Loopy registers, v and t are laid out as 0yyy NNYY YYYX XXXX: fine y, nametable, coarse y
+    // and coarse x
+    v: u16,     // Current VRAM address
+    t: u16,     // Temporary VRAM address, the top left corner of the screen
+    x: u8,      // Fine x scroll
+    w: bool,    // Write toggle shared by PPUSCROLL and PPUADDR, false on the first write
+    buffer: u8, // PPUDATA read buffer, reads return the value fetched by the previous read
+
+    // Data bus between the CPU and the PPU, write-only registers and the unused bits of
+    // PPUSTATUS read back the last value it carried
+    io_latch: u8,
+
+    scanline: u16,
+    dot: u16,
+    frame_count: u64,        // Frames completed since power up
+    vblank_suppressed: bool, // PPUSTATUS was read just before VBlank starts
+
+    // Background pipeline. The fetches of a tile fill the latches, which are loaded every 8 dots
+    // in the low byte of the shift registers. The registers shift once per dot and the pixel is
+    // taken from the bit selected by fine x, so they always hold the current and the next tile.
+    bg_tile: u8,
+    bg_palette: u8,
+    bg_pattern_lo: u8,
+    bg_pattern_hi: u8,
+    bg_shift_pattern_lo: u16,
+    bg_shift_pattern_hi: u16,
+    bg_shift_palette_lo: u16, // Palette bits expanded to 8 bits per tile
+    bg_shift_palette_hi: u16,
+
+    // Sprites of the next scanline, evaluated and fetched at the end of the current one. Pattern
+    // bytes are stored already flipped so bit 7 is always the leftmost pixel.
+    secondary_oam: [u8; SPRITES_PER_SCANLINE * 4],
+    sprite_count: usize,
+    sprite_zero_loaded: bool, // The first sprite slot holds sprite 0, for sprite-0 hit
+    sprite_pattern_lo: [u8; SPRITES_PER_SCANLINE],
+    sprite_pattern_hi: [u8; SPRITES_PER_SCANLINE],
+    sprite_attributes: [u8; SPRITES_PER_SCANLINE],
+    sprite_x: [u8; SPRITES_PER_SCANLINE],
+
+    // Colour of every pixel, SCREEN_WIDTH x SCREEN_HEIGHT. The low 6 bits are the palette entry and
+    // bits 6-8 the emphasis bits of PPUMASK, the palette conversion applies them. Pixels are drawn
+    // in the back buffer, the buffers are swapped when the frame is complete.
+    frame: Vec<u16>,
+    back_frame: Vec<u16>,
+}
+
+impl Default for Ppu {
+    fn default() -> Self {
+        Self::new(Region::Ntsc)
+    }
+}
+
+impl Ppu {
+    pub fn new(region: Region) -> Self {
+        Self {
+            region,
+            name_table: [0; 4 * 1024],
+            palette: [0; 32],
+            oam: [0; 256],
+            ctrl: 0,
+            mask: 0,
+            status: 0,
+            oam_addr: 0,
+            v: 0,
+            t: 0,
+            x: 0,
+            w: false,
+            buffer: 0,
+            io_latch: 0,
+            scanline: 0,
+            dot: 0,
+            frame_count: 0,
+            vblank_suppressed: false,
+            bg_tile: 0,
+            bg_palette: 0,
+            bg_pattern_lo: 0,
+            bg_pattern_hi: 0,
+            bg_shift_pattern_lo: 0,
+            bg_shift_pattern_hi: 0,
+            bg_shift_palette_lo: 0,
+            bg_shift_palette_hi: 0,
+            secondary_oam: [0xFF; SPRITES_PER_SCANLINE * 4],
+            sprite_count: 0,
+            sprite_zero_loaded: false,
+            sprite_pattern_lo: [0; SPRITES_PER_SCANLINE],
+            sprite_pattern_hi: [0; SPRITES_PER_SCANLINE],
+            sprite_attributes: [0; SPRITES_PER_SCANLINE],
+            sprite_x: [0; SPRITES_PER_SCANLINE],
+            frame: vec![0; SCREEN_WIDTH * SCREEN_HEIGHT],
+            back_frame: vec![0; SCREEN_WIDTH * SCREEN_HEIGHT],
+        }
+    }
+
+    pub fn ctrl(&self) -> u8 {
+        self.ctrl
+    }
+
+    pub fn mask(&self) -> u8 {
+        self.mask
+    }
+
+    pub fn status(&self) -> u8 {
+        self.status
+    }
+
+    pub fn vram_addr(&self) -> u16 {
+        self.v
+    }
+
+    pub fn temp_vram_addr(&self) -> u16 {
+        self.t
+    }
+
+    pub fn fine_x(&self) -> u8 {
+        self.x
+    }
+
+    pub fn scanline(&self) -> u16 {
+        self.scanline
+    }
+
+    pub fn dot(&self) -> u16 {
+        self.dot
+    }
+
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    // Last completed frame, blank until the first one is
+    pub fn frame(&self) -> &[u16] {
+        &self.frame
+    }
+
+    // Level of the NMI line, high during VBlank when PPUCTRL enables it. Enabling it during VBlank
+    // raises the line and triggers an NMI right away. When VBlank starts the line only rises 2
+    // dots after the flag is set, a PPUSTATUS read in between clears the flag first and the NMI of
+    // the frame is missed.
+    pub fn nmi(&self) -> bool {
+        let vblank_starting = self.scanline == VBLANK_SCANLINE && self.dot <= 3;
+
+        self.status & PpuStatus::VBLANK != 0
+            && self.ctrl & PpuCtrl::NMI_ENABLE != 0
+            && !vblank_starting
+    }
+
+    fn rendering_enabled(&self) -> bool {
+        self.mask & (PpuMask::SHOW_BACKGROUND | PpuMask::SHOW_SPRITES) != 0
+    }
+
+    fn pre_render_scanline(&self) -> u16 {
+        self.region.scanlines_per_frame() - 1
+    }
+
+    // Visible and pre-render scanlines, the ones fetching tiles when rendering is enabled
+    fn rendering_scanline(&self) -> bool {
+        self.scanline < POST_RENDER_SCANLINE || self.scanline == self.pre_render_scanline()
+    }
+
+    // Register read by the CPU, with the side effects of PPUSTATUS, OAMDATA and PPUDATA
+    pub fn cpu_read(&mut self, addr: u16, cartridge: &Cartridge) -> u8 {
+        let data = match addr & 0x0007 {
+            PPUSTATUS => {
+                let data = self.cpu_peek(addr, cartridge);
+
+                // Reading in the 2 dots before VBlank starts returns it clear and it never gets
+                // set, so the NMI of this frame is missed
+                if self.scanline == VBLANK_SCANLINE && self.dot <= 1 {
+                    self.vblank_suppressed = true;
+                }
+
+                self.status &= !PpuStatus::VBLANK;
+                self.w = false;
+
+                data
+            }
+            OAMDATA => self.oam[self.oam_addr as usize],
+            PPUDATA => {
+                let data = self.cpu_peek(addr, cartridge);
+
+                // Palette reads are immediate, the buffer gets the nametable byte underneath
+                self.buffer = if self.v & 0x3FFF >= ADDR_PALETTE {
+                    self.ppu_read(self.v - 0x1000, cartridge)
+                } else {
+                    self.ppu_read(self.v, cartridge)
+                };
+                self.increment_vram_addr();
+
+                data
+            }
+            _ => self.io_latch,
+        };
+
+        self.io_latch = data;
+
+        data
+    }
+
+    // Register read without side effects, for debuggers
+    pub fn cpu_peek(&self, addr: u16, cartridge: &Cartridge) -> u8 {
+        match addr & 0x0007 {
+            PPUSTATUS => (self.status & 0xE0) | (self.io_latch & 0x1F),
+            OAMDATA => self.oam[self.oam_addr as usize],
+            PPUDATA if self.v & 0x3FFF >= ADDR_PALETTE => {
+                // Palette entries are 6 bits, the top 2 come from the latch
+                self.greyscale(self.ppu_read(self.v, cartridge)) | (self.io_latch & 0xC0)
+            }
+            PPUDATA => self.buffer,
+            _ => self.io_latch,
+        }
+    }
+
+    pub fn cpu_write(&mut self, addr: u16, data: u8, cartridge: &mut Cartridge) {
+        self.io_latch = data;
+
+        match addr & 0x0007 {
+            PPUCTRL => {
+                self.ctrl = data;
+                self.t = (self.t & !0x0C00) | ((data & PpuCtrl::NAMETABLE) as u16) << 10;
+            }
+            PPUMASK => self.mask = data,
+            OAMADDR => self.oam_addr = data,
+            OAMDATA => {
+                // Bits 2-4 of the sprite attributes don't exist
+                let data = if self.oam_addr & 0x03 == 2 {
+                    data & 0xE3
+                } else {
+                    data
+                };
+
+                self.oam[self.oam_addr as usize] = data;
+                self.oam_addr = self.oam_addr.wrapping_add(1);
+            }
+            PPUSCROLL if !self.w => {
+                self.t = (self.t & !0x001F) | (data >> 3) as u16;
+                self.x = data & 0x07;
+                self.w = true;
+            }
+            PPUSCROLL => {
+                self.t =
+                    (self.t & !0x73E0) | ((data & 0x07) as u16) << 12 | ((data >> 3) as u16) << 5;
+                self.w = false;
+            }
+            PPUADDR if !self.w => {
+                // The high byte only has 6 bits, bit 14 of t is cleared
+                self.t = (self.t & 0x00FF) | ((data & 0x3F) as u16) << 8;
+                self.w = true;
+            }
+            PPUADDR => {
+                self.t = (self.t & 0xFF00) | data as u16;
+                self.v = self.t;
+                self.w = false;
+            }
+            PPUDATA => {
+                self.ppu_write(self.v, data, cartridge);
+                self.increment_vram_addr();
+            }
+            _ => {}
+        }
+    }
+
+    // PPUDATA accesses move across or down the nametable. While rendering they increment coarse x
+    // and y at once instead.
+    fn increment_vram_addr(&mut self) {
+        if self.rendering_enabled() && self.rendering_scanline() {
+            self.increment_x();
+            self.increment_y();
+
+            return;
+        }
+
+        let increment = if self.ctrl & PpuCtrl::INCREMENT_32 != 0 {
+            32
+        } else {
+            1
+        };
+
+        self.v = (self.v + increment) & 0x7FFF;
+    }
+
+    // Advances one dot
+    pub fn tick(&mut self, cartridge: &mut Cartridge) {
+        if self.scanline == VBLANK_SCANLINE && self.dot == 1 {
+            if !self.vblank_suppressed {
+                self.status |= PpuStatus::VBLANK;
+            }
+
+            self.vblank_suppressed = false;
+        }
+
+        if self.scanline == self.pre_render_scanline() && self.dot == 1 {
+            self.status &=
+                !(PpuStatus::VBLANK | PpuStatus::SPRITE_ZERO_HIT | PpuStatus::SPRITE_OVERFLOW);
+        }
+
+        if self.rendering_enabled() && self.rendering_scanline() {
+            self.fetch_background(cartridge);
+
+            match self.dot {
+                257 if self.scanline == self.pre_render_scanline() => {
+                    self.sprite_count = 0;
+                    self.sprite_zero_loaded = false;
+                }
+                257 => {
+                    self.evaluate_sprites();
+                    self.fetch_sprites(cartridge);
+                }
+                _ => {}
+            }
+
+            // OAMADDR is cleared while the sprites are fetched
+            if (257..=320).contains(&self.dot) {
+                self.oam_addr = 0;
+            }
+        }
+
+        if self.scanline < POST_RENDER_SCANLINE && (1..=SCREEN_WIDTH as u16).contains(&self.dot) {
+            self.draw_pixel(cartridge);
+        }
+
+        // NTSC odd frames skip the last dot of the pre-render scanline when rendering is enabled
+        if self.region == Region::Ntsc
+            && self.scanline == self.pre_render_scanline()
+            && self.dot == DOTS_PER_SCANLINE - 2
+            && self.frame_count % 2 == 1
+            && self.rendering_enabled()
+        {
+            self.dot += 1;
+        }
+
+        self.dot += 1;
+
+        if self.dot == DOTS_PER_SCANLINE {
+            self.dot = 0;
+            self.scanline += 1;
+
+            if self.scanline == POST_RENDER_SCANLINE {
+                std::mem::swap(&mut self.frame, &mut self.back_frame);
+                self.frame_count += 1;
+            }
+
+            if self.scanline == self.region.scanlines_per_frame() {
+                self.scanline = 0;
+            }
+        }
+    }
+
+    // Dots 1-256 fetch the tiles of the scanline and 321-336 the first two of the next one, each
+    // tile takes 8 dots: nametable, attribute, pattern low and pattern high bytes
+    fn fetch_background(&mut self, cartridge: &Cartridge) {
+        let dot = self.dot;
+
+        if (2..=257).contains(&dot) || (321..=337).contains(&dot) {
+            self.shift_background();
+
+            match (dot - 1) % 8 {
+                0 => {
+                    self.load_background();
+                    self.bg_tile = self.ppu_read(ADDR_NAME_TABLE | (self.v & 0x0FFF), cartridge);
+                }
+                2 => {
+                    let addr = ADDR_ATTRIBUTE_TABLE
+                        | (self.v & 0x0C00)
+                        | ((self.v >> 4) & 0x38)
+                        | ((self.v >> 2) & 0x07);
+
+                    // Each attribute byte covers 4x4 tiles, 2 bits per 2x2 quadrant
+                    let shift = ((self.v >> 4) & 0x04) | (self.v & 0x02);
+                    self.bg_palette = (self.ppu_read(addr, cartridge) >> shift) & 0x03;
+                }
+                4 => self.bg_pattern_lo = self.ppu_read(self.bg_pattern_addr(), cartridge),
+                6 => self.bg_pattern_hi = self.ppu_read(self.bg_pattern_addr() + 8, cartridge),
+                7 => self.increment_x(),
+                _ => {}
+            }
+        }
+
+        match dot {
+            256 => self.increment_y(),
+            // The shifters were reloaded by the tile fetches above
+            257 => self.copy_x(),
+            // Unused nametable fetches
+            338 | 340 => {
+                self.bg_tile = self.ppu_read(ADDR_NAME_TABLE | (self.v & 0x0FFF), cartridge);
+            }
+            280..=304 if self.scanline == self.pre_render_scanline() => self.copy_y(),
+            _ => {}
+        }
+    }
+
+    // Row of the current tile selected by fine y
+    fn bg_pattern_addr(&self) -> u16 {
+        let table = if self.ctrl & PpuCtrl::BACKGROUND_TABLE != 0 {
+            0x1000
+        } else {
+            0x0000
+        };
+
+        table + (self.bg_tile as u16) * 16 + ((self.v >> 12) & 0x07)
+    }
+
+    fn load_background(&mut self) {
+        self.bg_shift_pattern_lo = (self.bg_shift_pattern_lo & 0xFF00) | self.bg_pattern_lo as u16;
+        self.bg_shift_pattern_hi = (self.bg_shift_pattern_hi & 0xFF00) | self.bg_pattern_hi as u16;
+
+        let palette_lo = if self.bg_palette & 0x01 != 0 {
+            0xFF
+        } else {
+            0x00
+        };
+        let palette_hi = if self.bg_palette & 0x02 != 0 {
+            0xFF
+        } else {
+            0x00
+        };
+
+        self.bg_shift_palette_lo = (self.bg_shift_palette_lo & 0xFF00) | palette_lo;
+        self.bg_shift_palette_hi = (self.bg_shift_palette_hi & 0xFF00) | palette_hi;
+    }
+
+    fn shift_background(&mut self) {
+        self.bg_shift_pattern_lo <<= 1;
+        self.bg_shift_pattern_hi <<= 1;
+        self.bg_shift_palette_lo <<= 1;
+        self.bg_shift_palette_hi <<= 1;
+    }
+
+    // Moves to the next tile, wrapping into the horizontally adjacent nametable
+    fn increment_x(&mut self) {
+        if self.v & 0x001F == 31 {
+            self.v &= !0x001F;
+            self.v ^= 0x0400;
+        } else {
+            self.v += 1;
+        }
+    }
+
+    // Moves to the next pixel row, wrapping into the vertically adjacent nametable after row 29.
+    // Rows 30 and 31 hold the attributes, scrolling there wraps to row 0 of the same nametable.
+    fn increment_y(&mut self) {
+        if self.v & 0x7000 != 0x7000 {
+            self.v += 0x1000;
+            return;
+        }
+
+        self.v &= !0x7000;
+
+        let coarse_y = match (self.v >> 5) & 0x1F {
+            29 => {
+                self.v ^= 0x0800;
+                0
+            }
+            31 => 0,
+            coarse_y => coarse_y + 1,
+        };
+
+        self.v = (self.v & !0x03E0) | (coarse_y << 5);
+    }
+
+    // Restores coarse x and the horizontal nametable from t at the end of each scanline
+    fn copy_x(&mut self) {
+        self.v = (self.v & !0x041F) | (self.t & 0x041F);
+    }
+
+    // Restores fine y, coarse y and the vertical nametable from t before each frame
+    fn copy_y(&mut self) {
+        self.v = (self.v & !0x7BE0) | (self.t & 0x7BE0);
+    }
+
+    fn sprite_height(&self) -> u16 {
+        if self.ctrl & PpuCtrl::SPRITE_SIZE != 0 {
+            16
+        } else {
+            8
+        }
+    }
+
+    // Copies the first 8 sprites of the next scanline to secondary OAM. Once it is full the PPU
+    // keeps looking for a 9th sprite to set the overflow flag, but it wrongly increments the byte
+    // index along with the sprite index, so it compares tile, attribute and x bytes as y
+    // coordinates. This misses some overflows and reports some false ones.
+    fn evaluate_sprites(&mut self) {
+        let height = self.sprite_height();
+        let in_range = |y: u8| self.scanline.wrapping_sub(y as u16) < height;
+
+        self.secondary_oam = [0xFF; SPRITES_PER_SCANLINE * 4];
+        self.sprite_count = 0;
+        self.sprite_zero_loaded = false;
+
+        let mut m = 0;
+
+        for n in 0..64 {
+            if self.sprite_count < SPRITES_PER_SCANLINE {
+                let sprite = &self.oam[n * 4..n * 4 + 4];
+
+                if in_range(sprite[0]) {
+                    let slot = self.sprite_count * 4;
+
+                    self.secondary_oam[slot..slot + 4].copy_from_slice(sprite);
+                    self.sprite_count += 1;
+                    self.sprite_zero_loaded |= n == 0;
+                }
+            } else if in_range(self.oam[n * 4 + m]) {
+                self.status |= PpuStatus::SPRITE_OVERFLOW;
+                break;
+            } else {
+                m = (m + 1) % 4;
+            }
+        }
+    }
+
+    fn fetch_sprites(&mut self, cartridge: &Cartridge) {
+        let height = self.sprite_height();
+
+        for i in 0..self.sprite_count {
+            let [y, tile, attributes, x] =
+                [0, 1, 2, 3].map(|byte| self.secondary_oam[i * 4 + byte]);
+
+            let mut row = self.scanline.wrapping_sub(y as u16);
+
+            if attributes & 0x80 != 0 {
+                row = height - 1 - row;
+            }
+
+            // 8x16 sprites take the table from bit 0 of the tile index, the bottom half is the
+            // next tile
+            let addr = if height == 16 {
+                let table = (tile as u16 & 0x01) * 0x1000;
+                let tile = (tile & 0xFE) as u16 + row / 8;
+
+                table + tile * 16 + row % 8
+            } else {
+                let table = if self.ctrl & PpuCtrl::SPRITE_TABLE != 0 {
+                    0x1000
+                } else {
+                    0x0000
+                };
+
+                table + tile as u16 * 16 + row
+            };
+
+            let mut lo = self.ppu_read(addr, cartridge);
+            let mut hi = self.ppu_read(addr + 8, cartridge);
+
+            if attributes & 0x40 != 0 {
+                lo = lo.reverse_bits();
+                hi = hi.reverse_bits();
+            }
+
+            self.sprite_pattern_lo[i] = lo;
+            self.sprite_pattern_hi[i] = hi;
+            self.sprite_attributes[i] = attributes;
+            self.sprite_x[i] = x;
+        }
+    }
+
+    // The left 8 pixels of the background and the sprites can be hidden separately, clipped pixels
+    // are transparent so they can't trigger a sprite-0 hit either
+    fn background_pixel(&self, x: usize) -> (u8, u8) {
+        if self.mask & PpuMask::SHOW_BACKGROUND == 0
+            || (x < 8 && self.mask & PpuMask::SHOW_BACKGROUND_LEFT == 0)
+        {
+            return (0, 0);
+        }
+
+        let bit = 0x8000 >> self.x;
+
+        let pixel = (((self.bg_shift_pattern_hi & bit != 0) as u8) << 1)
+            | (self.bg_shift_pattern_lo & bit != 0) as u8;
+        let palette = (((self.bg_shift_palette_hi & bit != 0) as u8) << 1)
+            | (self.bg_shift_palette_lo & bit != 0) as u8;
+
+        (pixel, palette)
+    }
+
+    // First opaque sprite pixel at x in OAM order, with its slot
+    fn sprite_pixel(&self, x: usize) -> Option<(usize, u8)> {
+        if self.mask & PpuMask::SHOW_SPRITES == 0
+            || (x < 8 && self.mask & PpuMask::SHOW_SPRITES_LEFT == 0)
+        {
+            return None;
+        }
+
+        (0..self.sprite_count).find_map(|i| {
+            let column = x
+                .checked_sub(self.sprite_x[i] as usize)
+                .filter(|&c| c < 8)?;
+            let bit = 0x80 >> column;
+
+            let pixel = (((self.sprite_pattern_hi[i] & bit != 0) as u8) << 1)
+                | (self.sprite_pattern_lo[i] & bit != 0) as u8;
+
+            (pixel != 0).then_some((i, pixel))
+        })
+    }
+
+    fn draw_pixel(&mut self, cartridge: &Cartridge) {
+        let x = (self.dot - 1) as usize;
+        let y = self.scanline as usize;
+
+        let (bg_pixel, bg_palette) = self.background_pixel(x);
+        let sprite = self.sprite_pixel(x);
+
+        if let Some((slot, _)) = sprite
+            && slot == 0
+            && self.sprite_zero_loaded
+            && bg_pixel != 0
+            && x != 255
+        {
+            self.status |= PpuStatus::SPRITE_ZERO_HIT;
+        }
+
+        // Sprites are drawn in front of the background unless bit 5 of their attributes is set
+        let sprite =
+            sprite.filter(|&(slot, _)| bg_pixel == 0 || self.sprite_attributes[slot] & 0x20 == 0);
+
+        // With rendering disabled the backdrop is replaced by the palette entry v points to
+        let addr = if !self.rendering_enabled() && self.v & 0x3FFF >= ADDR_PALETTE {
+            self.v
+        } else if let Some((slot, pixel)) = sprite {
+            let palette = (self.sprite_attributes[slot] & 0x03) + 4;
+
+            ADDR_PALETTE + (palette << 2 | pixel) as u16
+        } else if bg_pixel == 0 {
+            ADDR_PALETTE
+        } else {
+            ADDR_PALETTE + (bg_palette << 2 | bg_pixel) as u16
+        };
+
+        let color = self.greyscale(self.ppu_read(addr, cartridge));
+        let emphasis = (self.mask & PpuMask::EMPHASIS) as u16;
+
+        self.back_frame[y * SCREEN_WIDTH + x] = emphasis << 1 | color as u16;
+    }
+
+    // Greyscale drops the hue of the palette entry, leaving the grey of the same brightness
+    fn greyscale(&self, color: u8) -> u8 {
+        if self.mask & PpuMask::GREYSCALE != 0 {
+            color & 0x30
+        } else {
+            color & 0x3F
+        }
+    }
+
+    // PPU address space, $0000-$3FFF mirrored up to $FFFF
+    pub fn ppu_read(&self, addr: u16, cartridge: &Cartridge) -> u8 {
+        let addr = addr & 0x3FFF;
+
+        match addr {
+            0x0000..=0x1FFF => cartridge.ppu_read(addr as usize),
+            0x2000..=0x3EFF => self.name_table[name_table_index(addr, cartridge.mirroring())],
+            _ => self.palette[palette_index(addr)],
+        }
+    }
+
+    pub fn ppu_write(&mut self, addr: u16, data: u8, cartridge: &mut Cartridge) {
+        let addr = addr & 0x3FFF;
+
+        match addr {
+            0x0000..=0x1FFF => cartridge.ppu_write(addr as usize, data),
+            0x2000..=0x3EFF => {
+                self.name_table[name_table_index(addr, cartridge.mirroring())] = data;
+            }
+            _ => self.palette[palette_index(addr)] = data,
+        }
+    }
+}
+
+// Offset in VRAM of a nametable address, $3000-$3EFF mirrors $2000-$2EFF
+fn name_table_index(addr: u16, mirroring: Mirroring) -> usize {
+    let addr = (addr - ADDR_NAME_TABLE) & 0x0FFF;
+    let table = addr / NAME_TABLE_SIZE;
+
+    let bank = match mirroring {
+        Mirroring::Horizontal => table / 2,
+        Mirroring::Vertical => table % 2,
+        Mirroring::FourScreen => table,
+        Mirroring::SingleScreenLower => 0,
+        Mirroring::SingleScreenUpper => 1,
+    };
+
+    (bank * NAME_TABLE_SIZE + addr % NAME_TABLE_SIZE) as usize
+}
+
+// Offset in palette RAM, the backdrop entries of the sprite palettes ($3F10, $3F14, $3F18 and
+// $3F1C) mirror the ones of the background palettes
+fn palette_index(addr: u16) -> usize {
+    let index = addr & 0x001F;
+
+    if index & 0x0013 == 0x0010 {
+        (index & !0x0010) as usize
+    } else {
+        index as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NROM cartridge with CHR RAM and horizontal mirroring
+    fn cartridge() -> Cartridge {
+        Cartridge::from_program("nop").unwrap()
+    }
+
+    // Points v to addr through PPUADDR
+    fn set_vram_addr(ppu: &mut Ppu, cartridge: &mut Cartridge, addr: u16) {
+        ppu.cpu_write(PPUADDR, (addr >> 8) as u8, cartridge);
+        ppu.cpu_write(PPUADDR, addr as u8, cartridge);
+    }
+
+    #[test]
+    fn status_read_clears_vblank_and_toggle() {
+        let mut cartridge = cartridge();
+        let mut ppu = Ppu {
+            status: PpuStatus::VBLANK,
+            ..Default::default()
+        };
+        ppu.cpu_write(PPUSCROLL, 0, &mut cartridge);
+        assert!(ppu.w);
+
+        assert_eq!(
+            ppu.cpu_read(PPUSTATUS, &cartridge) & PpuStatus::VBLANK,
+            PpuStatus::VBLANK
+        );
+        assert_eq!(ppu.status & PpuStatus::VBLANK, 0);
+        assert!(!ppu.w);
+
+        assert_eq!(ppu.cpu_read(PPUSTATUS, &cartridge) & PpuStatus::VBLANK, 0);
+    }
+
+    #[test]
+    fn scroll_writes() {
+        let mut cartridge = cartridge();
+        let mut ppu = Ppu::default();
+
+        // x = 125: coarse x 15, fine x 5
+        ppu.cpu_write(PPUSCROLL, 125, &mut cartridge);
+        assert_eq!((ppu.t, ppu.x, ppu.w), (0x000F, 5, true));
+
+        // y = 94: coarse y 11, fine y 6
+        ppu.cpu_write(PPUSCROLL, 94, &mut cartridge);
+        assert_eq!((ppu.t, ppu.x, ppu.w), (0x616F, 5, false));
+
+        // PPUCTRL selects the nametable
+        ppu.cpu_write(PPUCTRL, 0x03, &mut cartridge);
+        assert_eq!(ppu.t, 0x6D6F);
+        assert_eq!(ppu.v, 0);
+    }
+
+    #[test]
+    fn address_writes() {
+        let mut cartridge = cartridge();
+        let mut ppu = Ppu {
+            t: 0x7FFF,
+            ..Default::default()
+        };
+
+        // Bit 14 is cleared by the first write, v is only updated by the second one
+        ppu.cpu_write(PPUADDR, 0x3D, &mut cartridge);
+        assert_eq!((ppu.t, ppu.v, ppu.w), (0x3DFF, 0, true));
+
+        ppu.cpu_write(PPUADDR, 0xF0, &mut cartridge);
+        assert_eq!((ppu.t, ppu.v, ppu.w), (0x3DF0, 0x3DF0, false));
+    }
+
+    #[test]
+    fn buffered_data_read() {
+        let mut cartridge = cartridge();
+        let mut ppu = Ppu::default();
+
+        set_vram_addr(&mut ppu, &mut cartridge, 0x2000);
+        ppu.cpu_write(PPUDATA, 0x01, &mut cartridge);
+        ppu.cpu_write(PPUDATA, 0x02, &mut cartridge);
+
+        // Each read returns the byte fetched by the previous one
+        set_vram_addr(&mut ppu, &mut cartridge, 0x2000);
+        assert_eq!(ppu.cpu_read(PPUDATA, &cartridge), 0x00);
+        assert_eq!(ppu.cpu_read(PPUDATA, &cartridge), 0x01);
+        assert_eq!(ppu.cpu_read(PPUDATA, &cartridge), 0x02);
+    }
+
+    #[test]
+    fn palette_data_read() {
+        let mut cartridge = cartridge();
+        let mut ppu = Ppu::default();
+
+        set_vram_addr(&mut ppu, &mut cartridge, 0x2F11);
+        ppu.cpu_write(PPUDATA, 0x55, &mut cartridge);
+        set_vram_addr(&mut ppu, &mut cartridge, 0x3F11);
+        ppu.cpu_write(PPUDATA, 0x2A, &mut cartridge);
+
+        // The palette entry is returned right away, the buffer gets the nametable byte underneath
+        set_vram_addr(&mut ppu, &mut cartridge, 0x3F11);
+        assert_eq!(ppu.cpu_read(PPUDATA, &cartridge), 0x2A);
+        assert_eq!(ppu.buffer, 0x55);
+    }
+
+    #[test]
+    fn data_increment() {
+        let mut cartridge = cartridge();
+        let mut ppu = Ppu::default();
+
+        set_vram_addr(&mut ppu, &mut cartridge, 0x2000);
+        ppu.cpu_write(PPUDATA, 0, &mut cartridge);
+        assert_eq!(ppu.v, 0x2001);
+
+        ppu.cpu_write(PPUCTRL, PpuCtrl::INCREMENT_32, &mut cartridge);
+        ppu.cpu_write(PPUDATA, 0, &mut cartridge);
+        assert_eq!(ppu.v, 0x2021);
+
+        ppu.cpu_read(PPUDATA, &cartridge);
+        assert_eq!(ppu.v, 0x2041);
+    }
+
+    #[test]
+    fn name_table_mirroring() {
+        // VRAM bank seen at $2000, $2400, $2800 and $2C00
+        let cases = [
+            (Mirroring::Horizontal, [0, 0, 1, 1]),
+            (Mirroring::Vertical, [0, 1, 0, 1]),
+            (Mirroring::FourScreen, [0, 1, 2, 3]),
+            (Mirroring::SingleScreenLower, [0, 0, 0, 0]),
+            (Mirroring::SingleScreenUpper, [1, 1, 1, 1]),
+        ];
+
+        for (mirroring, banks) in cases {
+            for (table, bank) in banks.into_iter().enumerate() {
+                let addr = ADDR_NAME_TABLE + table as u16 * NAME_TABLE_SIZE + 0x0123;
+                let expected = bank * NAME_TABLE_SIZE as usize + 0x0123;
+
+                assert_eq!(name_table_index(addr, mirroring), expected);
+
+                // $3000-$3EFF mirrors $2000-$2EFF
+                assert_eq!(name_table_index(addr + 0x1000, mirroring), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn palette_mirroring() {
+        let cases = [
+            (0x3F00, 0x00),
+            (0x3F01, 0x01),
+            (0x3F04, 0x04),
+            (0x3F10, 0x00),
+            (0x3F11, 0x11),
+            (0x3F14, 0x04),
+            (0x3F18, 0x08),
+            (0x3F1C, 0x0C),
+            (0x3F1F, 0x1F),
+            (0x3F20, 0x00),
+            (0x3FF0, 0x00),
+        ];
+
+        for (addr, index) in cases {
+            assert_eq!(palette_index(addr), index, "${:04X}", addr);
+        }
+    }
+
+    // Runs until a new frame is complete, from the pre-render scanline so its fetches happen
+    fn run_frame(ppu: &mut Ppu, cartridge: &mut Cartridge) {
+        let frame_count = ppu.frame_count;
+
+        while ppu.frame_count < frame_count + 2 {
+            ppu.tick(cartridge);
+        }
+    }
+
+    // Writes tile 1 to CHR RAM, opaque with colour 1 everywhere
+    fn write_opaque_tile(ppu: &mut Ppu, cartridge: &mut Cartridge) {
+        for row in 0..8 {
+            ppu.ppu_write(0x0010 + row, 0xFF, cartridge);
+        }
+    }
+
+    #[test]
+    fn background_tile_in_frame() {
+        let mut cartridge = cartridge();
+        let mut ppu = Ppu::default();
+
+        write_opaque_tile(&mut ppu, &mut cartridge);
+        ppu.ppu_write(0x2000, 0x01, &mut cartridge);
+        ppu.ppu_write(0x3F00, 0x0F, &mut cartridge);
+        ppu.ppu_write(0x3F01, 0x16, &mut cartridge);
+        ppu.mask = PpuMask::SHOW_BACKGROUND | PpuMask::SHOW_BACKGROUND_LEFT;
+
+        run_frame(&mut ppu, &mut cartridge);
+
+        let frame = ppu.frame();
+        for y in 0..16 {
+            for x in 0..16 {
+                let expected = if x < 8 && y < 8 { 0x16 } else { 0x0F };
+                assert_eq!(
+                    frame[y * SCREEN_WIDTH + x],
+                    expected,
+                    "pixel ({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+
+    // Renders a frame with the opaque tile everywhere in the background and sprite 0 at (x, 30),
+    // returns whether it hit
+    fn sprite_zero_hit(x: u8, mask: u8) -> bool {
+        let mut cartridge = cartridge();
+        let mut ppu = Ppu::default();
+
+        write_opaque_tile(&mut ppu, &mut cartridge);
+        for addr in 0x2000..0x23C0 {
+            ppu.ppu_write(addr, 0x01, &mut cartridge);
+        }
+
+        ppu.oam = [0xFF; 256];
+        ppu.oam[..4].copy_from_slice(&[30, 0x01, 0x00, x]);
+        ppu.mask = mask;
+
+        run_frame(&mut ppu, &mut cartridge);
+
+        ppu.status & PpuStatus::SPRITE_ZERO_HIT != 0
+    }
+
+    const SHOW_ALL: u8 = PpuMask::SHOW_BACKGROUND
+        | PpuMask::SHOW_SPRITES
+        | PpuMask::SHOW_BACKGROUND_LEFT
+        | PpuMask::SHOW_SPRITES_LEFT;
+
+    #[test]
+    fn sprite_zero_hit_over_background() {
+        assert!(sprite_zero_hit(16, SHOW_ALL));
+        assert!(sprite_zero_hit(0, SHOW_ALL));
+    }
+
+    #[test]
+    fn no_sprite_zero_hit_at_last_pixel() {
+        // Only the pixel at x = 255 overlaps the screen
+        assert!(!sprite_zero_hit(255, SHOW_ALL));
+    }
+
+    #[test]
+    fn no_sprite_zero_hit_when_clipped() {
+        assert!(!sprite_zero_hit(0, SHOW_ALL & !PpuMask::SHOW_SPRITES_LEFT));
+        assert!(!sprite_zero_hit(
+            0,
+            SHOW_ALL & !PpuMask::SHOW_BACKGROUND_LEFT
+        ));
+
+        // Pixels past the clipped column still hit
+        assert!(sprite_zero_hit(4, SHOW_ALL & !PpuMask::SHOW_SPRITES_LEFT));
+    }
+
+    // Evaluates the sprites of scanline 50, the sprites listed at the start of OAM and every other
+    // byte off screen
+    fn sprite_overflow(sprites: &[[u8; 4]]) -> bool {
+        let mut ppu = Ppu {
+            oam: [0xF0; 256],
+            scanline: 50,
+            ..Default::default()
+        };
+
+        for (i, sprite) in sprites.iter().enumerate() {
+            ppu.oam[i * 4..i * 4 + 4].copy_from_slice(sprite);
+        }
+
+        ppu.evaluate_sprites();
+
+        ppu.status & PpuStatus::SPRITE_OVERFLOW != 0
+    }
+
+    #[test]
+    fn sprite_overflow_bug() {
+        let on_line = [50, 0xF0, 0xF0, 0xF0];
+        let off_line = [0xF0; 4];
+
+        assert!(!sprite_overflow(&[on_line; 8]));
+        assert!(sprite_overflow(&[on_line; 9]));
+
+        // After an off line 9th sprite the byte checked moves to the tile index: a 10th sprite on
+        // the line is missed, a tile index that looks in range is a false overflow
+        let mut sprites = [on_line; 10];
+        sprites[8] = off_line;
+        assert!(!sprite_overflow(&sprites));
+
+        sprites[9] = [0xF0, 50, 0xF0, 0xF0];
+        assert!(sprite_overflow(&sprites));
+    }
+
+    // Runs until the PPU reaches the dot of the scanline
+    fn run_to(ppu: &mut Ppu, cartridge: &mut Cartridge, scanline: u16, dot: u16) {
+        while (ppu.scanline, ppu.dot) != (scanline, dot) {
+            ppu.tick(cartridge);
+        }
+    }
+
+    // Reads PPUSTATUS when the PPU is about to run the dot of the VBlank scanline, returns whether
+    // the read saw VBlank and whether the NMI line rose
+    fn vblank_race(dot: u16) -> (bool, bool) {
+        let mut cartridge = cartridge();
+        let mut ppu = Ppu {
+            ctrl: PpuCtrl::NMI_ENABLE,
+            scanline: POST_RENDER_SCANLINE,
+            ..Default::default()
+        };
+
+        let mut nmi = false;
+
+        while (ppu.scanline, ppu.dot) != (VBLANK_SCANLINE, dot) {
+            ppu.tick(&mut cartridge);
+            nmi |= ppu.nmi();
+        }
+
+        let vblank = ppu.cpu_read(PPUSTATUS, &cartridge) & PpuStatus::VBLANK != 0;
+
+        while ppu.dot < 20 {
+            ppu.tick(&mut cartridge);
+            nmi |= ppu.nmi();
+        }
+
+        (vblank, nmi)
+    }
+
+    #[test]
+    fn vblank_status_read_race() {
+        // The flag is set by dot 1
+        assert_eq!(vblank_race(0), (false, false));
+        assert_eq!(vblank_race(1), (false, false));
+        assert_eq!(vblank_race(2), (true, false));
+        assert_eq!(vblank_race(3), (true, false));
+        assert_eq!(vblank_race(4), (true, true));
+        assert_eq!(vblank_race(5), (true, true));
+    }
+
+    #[test]
+    fn vblank_without_status_read() {
+        let mut cartridge = cartridge();
+        let mut ppu = Ppu {
+            ctrl: PpuCtrl::NMI_ENABLE,
+            ..Default::default()
+        };
+
+        run_to(&mut ppu, &mut cartridge, VBLANK_SCANLINE, 4);
+        assert!(ppu.nmi());
+
+        // Cleared by the pre-render scanline
+        let pre_render_scanline = ppu.pre_render_scanline();
+        run_to(&mut ppu, &mut cartridge, pre_render_scanline, 2);
+        assert!(!ppu.nmi());
+        assert_eq!(ppu.status & PpuStatus::VBLANK, 0);
+    }
+}
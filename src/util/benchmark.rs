@@ -0,0 +1,57 @@
+use crate::nes::Nes;
+use std::time::Instant;
+
+// Speed of the NTSC 2A03, 21.477272 MHz master clock divided by 12
+const NTSC_CPU_CLOCK: f64 = 1_789_773.0;
+
+pub const BENCHMARK_INSTRUCTIONS: u64 = 50_000_000;
+
+// Tight loop going through the common addressing modes, reads, writes and read-modify-writes
+const BENCHMARK_PROGRAM: &str = "
+reset:
+    ldx #0
+    ldy #0
+    lda #$00
+    sta $20
+    lda #$04
+    sta $21
+loop:
+    lda $0200,x
+    adc #1
+    sta $0200,x
+    inc $10
+    lda ($20),y
+    eor $11
+    sta $0300,y
+    asl a
+    inx
+    iny
+    bne loop
+    jmp loop
+";
+
+// Measures how many instructions per second the CPU runs through the NES bus
+pub fn run_benchmark(instructions: u64) -> Result<(), String> {
+    let mut nes = Nes::from_program(BENCHMARK_PROGRAM)?;
+
+    nes.reset();
+
+    let start = Instant::now();
+    let cycles = nes
+        .run_instructions(instructions)
+        .map_err(|jam| jam.to_string())?;
+    let seconds = start.elapsed().as_secs_f64();
+
+    println!(
+        "{} instructions, {} cycles in {:.3} s",
+        instructions, cycles, seconds
+    );
+    println!(
+        "{:.2} M instructions/s, {:.2} MHz ({:.1}x an NTSC 2A03)",
+        instructions as f64 / seconds / 1e6,
+        cycles as f64 / seconds / 1e6,
+        cycles as f64 / seconds / NTSC_CPU_CLOCK
+    );
+
+    Ok(())
+}
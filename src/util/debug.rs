@@ -1,25 +1,49 @@
 use crate::nes::{
-    Nes, bus::ADDR_PRG_ROM, bus::Bus, cpu::Cpu, cpu::CpuState, cpu::StatusFlags, cpu::has_flag,
-    instructions::AddrMode, instructions::Instruction, instructions::get_instruction,
+    Nes, ScreenshotOptions, assembler::assemble, bus::ADDR_PRG_ROM, cpu::CpuState,
+    cpu::StatusFlags, cpu::has_flag, disassembler::disassemble, palette::Palette,
 };
 use colored::Colorize;
 use std::collections::BTreeMap;
 use std::io::{Write, stdin, stdout};
 
-// Small program that multiplies 10 by 3 and stores the result at address 0x0002:
-// "A2 0A 8E 00 00 A2 03 8E 01 00 AC 00 00 A9 00 18 6D 01 00 88 D0 FA 8D 02 00 EA EA EA"
+// Small program that multiplies 10 by 3 and stores the result at address $0002
+pub const MULTIPLY_PROGRAM: &str = "
+    .org $8000
+reset:
+    ldx #10
+    stx $00
+    ldx #3
+    stx $01
+    ldy $00
+    lda #0
+    clc
+loop:
+    adc $01
+    dey
+    bne loop
+    sta $02
+    nop
+    nop
+    nop
+";
+
+// Where the debugger saves screenshots, in the current directory
+const SCREENSHOT_PATH: &str = "screenshot.png";
 
 pub fn debug_cpu(program: &str) -> Result<(), String> {
+    let prg_rom = assemble(program).map_err(|e| e.to_string())?.prg_rom;
     let mut nes = Nes::from_program(program)?;
 
     nes.reset();
-    nes.cpu.step_to_next_instruction(&mut nes.bus);
 
-    let lines = disassemble(
-        &nes.bus,
-        ADDR_PRG_ROM as u16,
-        (ADDR_PRG_ROM + program.len()) as u16,
-    );
+    let lines: BTreeMap<u16, String> = disassemble(&prg_rom)?
+        .lines
+        .into_iter()
+        .map(|line| (line.addr, format!("${:04X}: {}", line.addr, line.text)))
+        .collect();
+
+    let palette = Palette::default();
+    let mut message = None;
 
     loop {
         let state = nes.cpu.get_state();
@@ -38,16 +62,33 @@ pub fn debug_cpu(program: &str) -> Result<(), String> {
         println!();
         print_instructions(&lines, state.pc);
 
-        println!("\nPress Enter to step, q to quit...");
+        if let Some(message) = message.take() {
+            println!("\n{}", message);
+        }
+
+        println!("\nPress Enter to step, s to save a screenshot, q to quit...");
 
         let mut input = String::new();
         stdin().read_line(&mut input).unwrap();
 
-        if input.trim().to_lowercase() == "q" {
-            break;
+        match input.trim().to_lowercase().as_str() {
+            "q" => break,
+            "s" => {
+                let options = ScreenshotOptions::default();
+
+                message = Some(
+                    match nes.save_screenshot(SCREENSHOT_PATH, &palette, &options) {
+                        Ok(()) => format!("Saved the last frame to {}", SCREENSHOT_PATH),
+                        Err(e) => format!("Failed to save {}: {}", SCREENSHOT_PATH, e),
+                    },
+                );
+
+                continue;
+            }
+            _ => {}
         }
 
-        nes.cpu.step_to_next_instruction(&mut nes.bus);
+        nes.cpu.step(&mut nes.bus).map_err(|jam| jam.to_string())?;
     }
 
     Ok(())
@@ -114,122 +155,3 @@ fn print_instructions(instructions: &BTreeMap<u16, String>, current_pc: u16) {
         }
     }
 }
-
-fn disassemble(bus: &Bus, start: u16, end: u16) -> BTreeMap<u16, String> {
-    let mut addr = start;
-
-    let mut lines: BTreeMap<u16, String> = BTreeMap::new();
-
-    while addr <= end {
-        let line_addr = addr;
-
-        let mut instruction_str = format!("${:04X}: ", addr);
-
-        let opcode = bus.cpu_read(addr, true);
-        addr += 1;
-
-        let Instruction { name, mode, .. } = get_instruction(opcode);
-
-        instruction_str = format!("{}{} ", instruction_str, name.to_uppercase());
-
-        match mode {
-            AddrMode::Imp => {
-                instruction_str = format!("{}{}", instruction_str, "{IMP}");
-            }
-            AddrMode::Imm => {
-                let value = bus.cpu_read(addr, true);
-                addr += 1;
-
-                instruction_str = format!("{}#${:02X} {}", instruction_str, value, "{IMM}");
-            }
-            AddrMode::Zp0 => {
-                let lo = bus.cpu_read(addr, true);
-                addr += 1;
-
-                instruction_str = format!("{}${:02X} {}", instruction_str, lo, "{ZP0}");
-            }
-            AddrMode::Zpx => {
-                let lo = bus.cpu_read(addr, true);
-                addr += 1;
-
-                instruction_str = format!("{}${:02X}, X {}", instruction_str, lo, "{ZPX}");
-            }
-            AddrMode::Zpy => {
-                let lo = bus.cpu_read(addr, true);
-                addr += 1;
-
-                instruction_str = format!("{}${:02X}, Y {}", instruction_str, lo, "{ZPY}");
-            }
-            AddrMode::Rel => {
-                let value = bus.cpu_read(addr, true) as u16;
-                addr += 1;
-
-                let value = if value & 0x0080 != 0 {
-                    // If bit 7 isset, fill upper byte with 1s to preserve negative value
-                    value | 0xFF00
-                } else {
-                    value
-                };
-
-                instruction_str = format!(
-                    "{}${:02X} [${:04X}] {}",
-                    instruction_str,
-                    value,
-                    addr.wrapping_add(value),
-                    "{REL}"
-                );
-            }
-            AddrMode::Abs => {
-                let lo = bus.cpu_read(addr, true) as u16;
-                addr += 1;
-                let hi = bus.cpu_read(addr, true) as u16;
-                addr += 1;
-
-                instruction_str = format!("{}${:04X} {}", instruction_str, (hi << 8) | lo, "{ABS}");
-            }
-            AddrMode::Abx => {
-                let lo = bus.cpu_read(addr, true) as u16;
-                addr += 1;
-                let hi = bus.cpu_read(addr, true) as u16;
-                addr += 1;
-
-                instruction_str =
-                    format!("{}${:04X}, X {}", instruction_str, (hi << 8) | lo, "{ABX}");
-            }
-            AddrMode::Aby => {
-                let lo = bus.cpu_read(addr, true) as u16;
-                addr += 1;
-                let hi = bus.cpu_read(addr, true) as u16;
-                addr += 1;
-
-                instruction_str =
-                    format!("{}${:04X}, Y {}", instruction_str, (hi << 8) | lo, "{ABY}");
-            }
-            AddrMode::Ind => {
-                let lo = bus.cpu_read(addr, true) as u16;
-                addr += 1;
-                let hi = bus.cpu_read(addr, true) as u16;
-                addr += 1;
-
-                instruction_str =
-                    format!("{} (${:04X}) {}", instruction_str, (hi << 8) | lo, "{IND}");
-            }
-            AddrMode::Izx => {
-                let lo = bus.cpu_read(addr, true);
-                addr += 1;
-
-                instruction_str = format!("{}(${:02X}, X), {}", instruction_str, lo, "{IZX}");
-            }
-            AddrMode::Izy => {
-                let lo = bus.cpu_read(addr, true);
-                addr += 1;
-
-                instruction_str = format!("{}(${:02X}), Y {}", instruction_str, lo, "{IZY}");
-            }
-        }
-
-        lines.insert(line_addr, instruction_str.clone());
-    }
-
-    lines
-}
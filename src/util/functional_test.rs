@@ -0,0 +1,76 @@
+use crate::nes::{bus::FlatBus, cpu::Cpu, cpu::CpuState, cpu::StatusFlags, cpu::Variant};
+use std::fs;
+use std::path::Path;
+
+// Klaus Dormann's 6502_functional_test.bin is a full 64 KiB image: it is loaded at $0000, starts
+// at $0400 and loops forever on $3469 once every test passed
+pub const FUNCTIONAL_TEST_ORIGIN: u16 = 0x0000;
+pub const FUNCTIONAL_TEST_START: u16 = 0x0400;
+pub const FUNCTIONAL_TEST_SUCCESS: u16 = 0x3469;
+
+// Variant names accepted on the command line, the default build of the test checks decimal mode
+// so it needs the NMOS 6502
+pub fn parse_variant(name: &str) -> Result<Variant, String> {
+    match name.to_lowercase().as_str() {
+        "2a03" => Ok(Variant::Ricoh2A03),
+        "6502" => Ok(Variant::Nmos6502),
+        "65c02" => Ok(Variant::Wdc65C02),
+        _ => Err(format!("Unknown CPU variant: {} (2a03, 6502, 65c02)", name)),
+    }
+}
+
+// Runs the functional test until it traps, meaning an instruction jumps or branches to itself.
+// Failed tests trap on the instruction that detected the error, look up the address in the
+// listing to know which one. Also runs the 65C02 extended opcodes test.
+pub fn run_functional_test(
+    path: impl AsRef<Path>,
+    success: u16,
+    variant: Variant,
+) -> Result<(), String> {
+    let image = fs::read(path).map_err(|e| e.to_string())?;
+
+    let mut bus = FlatBus::default();
+    bus.load(FUNCTIONAL_TEST_ORIGIN, &image);
+
+    let mut cpu = Cpu::new(variant);
+    cpu.set_state(&CpuState {
+        a: 0,
+        x: 0,
+        y: 0,
+        sp: 0xFD,
+        pc: FUNCTIONAL_TEST_START,
+        p: StatusFlags::UNUSED | StatusFlags::INTERRUPT_DISABLE,
+        opcode: 0,
+        cycles: 0,
+    });
+
+    let mut instructions: u64 = 0;
+
+    loop {
+        let pc = cpu.get_state().pc;
+
+        cpu.step(&mut bus).map_err(|jam| jam.to_string())?;
+        instructions += 1;
+
+        if cpu.get_state().pc == pc {
+            break;
+        }
+    }
+
+    let trap = cpu.get_state().pc;
+
+    if trap == success {
+        println!(
+            "Functional test passed ({} instructions, {} cycles)",
+            instructions,
+            cpu.total_cycles()
+        );
+
+        Ok(())
+    } else {
+        Err(format!(
+            "Functional test trapped at ${:04X} after {} instructions",
+            trap, instructions
+        ))
+    }
+}
@@ -1 +1,5 @@
+pub mod benchmark;
 pub mod debug;
+pub mod functional_test;
+pub mod trace;
+pub mod viewers;
@@ -0,0 +1,257 @@
+use crate::nes::{
+    Nes, bus::CpuBus, cpu::CpuState, disassembler::format_operand, instructions::AddrMode,
+    instructions::Instruction, instructions::get_instruction,
+};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+// nestest.nes starts its automated mode at this address instead of the reset vector
+pub const NESTEST_START: u16 = 0xC000;
+
+// Mnemonic as spelled in nestest.log
+fn nestest_name(name: &str) -> String {
+    match name {
+        "isc" => "ISB".to_string(),
+        _ => name.to_uppercase(),
+    }
+}
+
+fn read_word(bus: &dyn CpuBus, lo_addr: u16, hi_addr: u16) -> u16 {
+    ((bus.peek(hi_addr) as u16) << 8) | bus.peek(lo_addr) as u16
+}
+
+// Disassembles the instruction at pc the way nestest.log does, memory operands are followed by
+// the effective address and the value it holds before the instruction executes
+fn disassemble_nestest(bus: &dyn CpuBus, state: &CpuState) -> String {
+    let pc = state.pc;
+    let opcode = bus.peek(pc);
+
+    let Instruction { name, mode, .. } = get_instruction(opcode);
+
+    let lo = bus.peek(pc.wrapping_add(1));
+    let hi = bus.peek(pc.wrapping_add(2));
+    let word = ((hi as u16) << 8) | lo as u16;
+
+    let operand = match mode {
+        AddrMode::Imp => String::new(),
+        AddrMode::Imm
+        | AddrMode::Zp0
+        | AddrMode::Zpx
+        | AddrMode::Zpy
+        | AddrMode::Izx
+        | AddrMode::Izy
+        | AddrMode::Izp => format!("${:02X}", lo),
+        AddrMode::Abs | AddrMode::Abx | AddrMode::Aby | AddrMode::Ind | AddrMode::Iax => {
+            format!("${:04X}", word)
+        }
+        AddrMode::Rel => format!("${:04X}", pc.wrapping_add(2).wrapping_add(lo as i8 as u16)),
+        // 65C02 only, not part of nestest.log
+        AddrMode::Zpr => format!(
+            "${:02X},${:04X}",
+            lo,
+            pc.wrapping_add(3).wrapping_add(hi as i8 as u16)
+        ),
+    };
+
+    // Effective address and value
+    let annotation = match mode {
+        AddrMode::Zp0 => format!(" = {:02X}", bus.peek(lo as u16)),
+        AddrMode::Zpx | AddrMode::Zpy => {
+            let index = if mode == AddrMode::Zpx {
+                state.x
+            } else {
+                state.y
+            };
+            let addr = lo.wrapping_add(index);
+
+            format!(" @ {:02X} = {:02X}", addr, bus.peek(addr as u16))
+        }
+        AddrMode::Abs if !matches!(name, "jmp" | "jsr") => format!(" = {:02X}", bus.peek(word)),
+        AddrMode::Abx | AddrMode::Aby => {
+            let index = if mode == AddrMode::Abx {
+                state.x
+            } else {
+                state.y
+            };
+            let addr = word.wrapping_add(index as u16);
+
+            format!(" @ {:04X} = {:02X}", addr, bus.peek(addr))
+        }
+        AddrMode::Ind => {
+            // Same page wrap bug as Cpu::ind
+            let target = read_word(bus, word, (word & 0xFF00) | (word.wrapping_add(1) & 0x00FF));
+
+            format!(" = {:04X}", target)
+        }
+        AddrMode::Izx => {
+            let ptr = lo.wrapping_add(state.x);
+            let addr = read_word(bus, ptr as u16, ptr.wrapping_add(1) as u16);
+
+            format!(" @ {:02X} = {:04X} = {:02X}", ptr, addr, bus.peek(addr))
+        }
+        AddrMode::Izy => {
+            let base = read_word(bus, lo as u16, lo.wrapping_add(1) as u16);
+            let addr = base.wrapping_add(state.y as u16);
+
+            format!(" = {:04X} @ {:04X} = {:02X}", base, addr, bus.peek(addr))
+        }
+        _ => String::new(),
+    };
+
+    format!(
+        "{} {}{}",
+        nestest_name(name),
+        format_operand(name, mode, &operand).to_uppercase(),
+        annotation
+    )
+    .trim_end()
+    .to_string()
+}
+
+// Formats the instruction about to be executed as a nestest.log line
+pub fn trace_line(nes: &Nes) -> String {
+    let state = nes.cpu.get_state();
+    let cycles = nes.cpu.total_cycles();
+    let bus = &nes.bus;
+
+    let instruction = get_instruction(bus.peek(state.pc));
+
+    let bytes = (0..instruction.byte_len())
+        .map(|i| format!("{:02X}", bus.peek(state.pc.wrapping_add(i))))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let marker = if instruction.official { ' ' } else { '*' };
+
+    format!(
+        "{:04X}  {:<8} {}{:<32}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} PPU:{:>3},{:>3} CYC:{}",
+        state.pc,
+        bytes,
+        marker,
+        disassemble_nestest(bus, &state),
+        state.a,
+        state.x,
+        state.y,
+        state.p,
+        state.sp,
+        bus.ppu.scanline(),
+        bus.ppu.dot(),
+        cycles
+    )
+}
+
+// Resets the NES and optionally moves the program counter to start
+fn power_up(nes: &mut Nes, start: Option<u16>) {
+    nes.reset();
+
+    if let Some(pc) = start {
+        nes.cpu.set_pc(pc);
+    }
+}
+
+// Writes one nestest.log line per executed instruction until the CPU jams or max_instructions
+// have been traced
+pub fn trace(
+    nes: &mut Nes,
+    start: Option<u16>,
+    max_instructions: Option<usize>,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    power_up(nes, start);
+    let mut count = 0;
+
+    while max_instructions.is_none_or(|max| count < max) {
+        writeln!(out, "{}", trace_line(nes)).map_err(|e| e.to_string())?;
+
+        nes.cpu.step(&mut nes.bus).map_err(|jam| jam.to_string())?;
+        count += 1;
+    }
+
+    Ok(())
+}
+
+// Runs the NES alongside a golden log and reports the first line that differs.
+// Returns the number of matching lines.
+pub fn compare_with_log(
+    nes: &mut Nes,
+    start: Option<u16>,
+    golden: impl BufRead,
+) -> Result<usize, String> {
+    power_up(nes, start);
+    let mut matched = 0;
+
+    for (i, expected) in golden.lines().enumerate() {
+        let expected = expected.map_err(|e| e.to_string())?;
+        let actual = trace_line(nes);
+
+        if actual.trim_end() != expected.trim_end() {
+            return Err(format!(
+                "Trace diverges at line {}:\nexpected: {}\nactual:   {}",
+                i + 1,
+                expected.trim_end(),
+                actual
+            ));
+        }
+
+        nes.cpu
+            .step(&mut nes.bus)
+            .map_err(|jam| format!("Line {}: {}", i + 1, jam))?;
+        matched += 1;
+    }
+
+    Ok(matched)
+}
+
+// Traces nestest.nes in automated mode and compares it against nestest.log
+pub fn run_nestest(rom: impl AsRef<Path>, log: impl AsRef<Path>) -> Result<(), String> {
+    let mut nes = Nes::from_rom(rom).map_err(|e| e.to_string())?;
+    let golden = BufReader::new(File::open(log).map_err(|e| e.to_string())?);
+
+    let matched = compare_with_log(&mut nes, Some(NESTEST_START), golden)?;
+
+    println!("Trace matches all {} lines of the golden log", matched);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Official and unofficial opcodes, the memory annotations of the addressing modes and the
+    // PPU and cycle counters after the reset
+    #[test]
+    fn trace_lines() {
+        let program = "
+            reset:  ldx #$05
+                    stx $10
+                    nop $10
+                    isc $10
+                    lda ($10),y
+                    lda $0200,x
+                    lsr a
+                    jmp (vector)
+            vector: .word reset
+        ";
+        let mut nes = Nes::from_program(program).unwrap();
+        let mut out = vec![];
+
+        trace(&mut nes, None, Some(9), &mut out).unwrap();
+
+        let expected = [
+            "8000  A2 05     LDX #$05                        A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7",
+            "8002  86 10     STX $10 = 00                    A:00 X:05 Y:00 P:24 SP:FD PPU:  0, 27 CYC:9",
+            "8004  04 10    *NOP $10 = 05                    A:00 X:05 Y:00 P:24 SP:FD PPU:  0, 36 CYC:12",
+            "8006  E7 10    *ISB $10 = 05                    A:00 X:05 Y:00 P:24 SP:FD PPU:  0, 45 CYC:15",
+            "8008  B1 10     LDA ($10),Y = 0006 @ 0006 = 00  A:F9 X:05 Y:00 P:A4 SP:FD PPU:  0, 60 CYC:20",
+            "800A  BD 00 02  LDA $0200,X @ 0205 = 00         A:00 X:05 Y:00 P:26 SP:FD PPU:  0, 75 CYC:25",
+            "800D  4A        LSR A                           A:00 X:05 Y:00 P:26 SP:FD PPU:  0, 87 CYC:29",
+            "800E  6C 11 80  JMP ($8011) = 8000              A:00 X:05 Y:00 P:26 SP:FD PPU:  0, 93 CYC:31",
+            "8000  A2 05     LDX #$05                        A:00 X:05 Y:00 P:26 SP:FD PPU:  0,108 CYC:36",
+        ];
+
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().collect::<Vec<_>>(), expected);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::nes::{
+    Nes,
+    cartridge::Cartridge,
+    image::Image,
+    palette::Palette,
+    ppu::{ADDR_PALETTE, Ppu, PpuCtrl, SCREEN_HEIGHT, SCREEN_WIDTH},
+};
+use std::path::Path;
+
+const TILE_SIZE: usize = 8;
+const PATTERN_TABLE_TILES: usize = 16; // Tiles per row and per column of a pattern table
+
+// Colour of the scroll window outline in the nametable viewer
+const SCROLL_WINDOW_COLOR: [u8; 3] = [255, 0, 0];
+
+// RGB colour of an entry of the palette RAM
+fn palette_color(ppu: &Ppu, cartridge: &Cartridge, palette: &Palette, entry: u16) -> [u8; 3] {
+    palette.rgb((ppu.ppu_read(ADDR_PALETTE + entry, cartridge) & 0x3F) as u16)
+}
+
+// Pixel values of a row of the tile at addr in the pattern tables
+fn tile_row(ppu: &Ppu, cartridge: &Cartridge, addr: u16) -> [u8; TILE_SIZE] {
+    let lo = ppu.ppu_read(addr, cartridge);
+    let hi = ppu.ppu_read(addr + 8, cartridge);
+
+    std::array::from_fn(|column| {
+        let bit = 0x80 >> column;
+        (((hi & bit != 0) as u8) << 1) | (lo & bit != 0) as u8
+    })
+}
+
+// Both pattern tables side by side, coloured with one of the 8 palettes of the palette RAM
+pub fn pattern_tables(ppu: &Ppu, cartridge: &Cartridge, palette: &Palette, index: u8) -> Image {
+    let size = PATTERN_TABLE_TILES * TILE_SIZE;
+    let mut image = Image::new(size * 2, size);
+
+    let colors: [[u8; 3]; 4] = std::array::from_fn(|pixel| {
+        palette_color(
+            ppu,
+            cartridge,
+            palette,
+            (index as u16 & 0x07) * 4 + pixel as u16,
+        )
+    });
+
+    for tile in 0..PATTERN_TABLE_TILES * PATTERN_TABLE_TILES * 2 {
+        let x = (tile / 256) * size + (tile % PATTERN_TABLE_TILES) * TILE_SIZE;
+        let y = (tile % 256 / PATTERN_TABLE_TILES) * TILE_SIZE;
+
+        for row in 0..TILE_SIZE {
+            let pixels = tile_row(ppu, cartridge, (tile * 16 + row) as u16);
+
+            for (column, &pixel) in pixels.iter().enumerate() {
+                image.set_pixel(x + column, y + row, colors[pixel as usize]);
+            }
+        }
+    }
+
+    image
+}
+
+// The 4 logical nametables as a 2x2 grid, with the scroll window of the next frame outlined. The
+// window is taken from t, which holds the scroll position between frames.
+pub fn name_tables(ppu: &Ppu, cartridge: &Cartridge, palette: &Palette) -> Image {
+    let mut image = Image::new(SCREEN_WIDTH * 2, SCREEN_HEIGHT * 2);
+
+    let table = if ppu.ctrl() & PpuCtrl::BACKGROUND_TABLE != 0 {
+        0x1000
+    } else {
+        0x0000
+    };
+
+    for name_table in 0..4 {
+        let base = 0x2000 + name_table as u16 * 0x0400;
+        let origin_x = (name_table % 2) * SCREEN_WIDTH;
+        let origin_y = (name_table / 2) * SCREEN_HEIGHT;
+
+        for tile_y in 0..SCREEN_HEIGHT / TILE_SIZE {
+            for tile_x in 0..SCREEN_WIDTH / TILE_SIZE {
+                let tile = ppu.ppu_read(base + (tile_y * 32 + tile_x) as u16, cartridge);
+
+                // Each attribute byte covers 4x4 tiles, 2 bits per 2x2 quadrant
+                let attribute = ppu.ppu_read(
+                    base + 0x03C0 + (tile_y / 4 * 8 + tile_x / 4) as u16,
+                    cartridge,
+                );
+                let shift = (tile_y & 0x02) * 2 + (tile_x & 0x02);
+                let palette_index = (attribute >> shift) & 0x03;
+
+                for row in 0..TILE_SIZE {
+                    let addr = table + tile as u16 * 16 + row as u16;
+
+                    for (column, pixel) in tile_row(ppu, cartridge, addr).into_iter().enumerate() {
+                        let entry = if pixel == 0 {
+                            0
+                        } else {
+                            (palette_index * 4 + pixel) as u16
+                        };
+
+                        image.set_pixel(
+                            origin_x + tile_x * TILE_SIZE + column,
+                            origin_y + tile_y * TILE_SIZE + row,
+                            palette_color(ppu, cartridge, palette, entry),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    let t = ppu.temp_vram_addr() as usize;
+    let scroll_x = (t >> 10 & 0x01) * SCREEN_WIDTH + (t & 0x1F) * TILE_SIZE + ppu.fine_x() as usize;
+    let scroll_y =
+        (t >> 11 & 0x01) * SCREEN_HEIGHT + (t >> 5 & 0x1F) * TILE_SIZE + (t >> 12 & 0x07);
+
+    image.outline(
+        scroll_x,
+        scroll_y,
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        SCROLL_WINDOW_COLOR,
+    );
+
+    image
+}
+
+// The 64 sprites of OAM in an 8x8 grid, in OAM order with their palette and flips. Every cell is
+// 8x16 so both sprite sizes fit, transparent pixels show the backdrop colour.
+pub fn sprites(ppu: &Ppu, cartridge: &Cartridge, palette: &Palette) -> Image {
+    let cell_height = TILE_SIZE * 2;
+    let mut image = Image::new(TILE_SIZE * 8, cell_height * 8);
+
+    let tall = ppu.ctrl() & PpuCtrl::SPRITE_SIZE != 0;
+    let height = if tall { 16 } else { 8 };
+    let backdrop = palette_color(ppu, cartridge, palette, 0);
+
+    for sprite in 0..64 {
+        let [_, tile, attributes, _] = [0, 1, 2, 3].map(|byte| ppu.oam[sprite * 4 + byte]);
+        let x = (sprite % 8) * TILE_SIZE;
+        let y = (sprite / 8) * cell_height;
+
+        for row in 0..cell_height {
+            // Bottom half of the cell, unused by 8x8 sprites
+            if row >= height {
+                for column in 0..TILE_SIZE {
+                    image.set_pixel(x + column, y + row, backdrop);
+                }
+
+                continue;
+            }
+
+            let flipped_row = if attributes & 0x80 != 0 {
+                height - 1 - row
+            } else {
+                row
+            };
+
+            // Same addressing as the sprite fetches: 8x16 sprites take their table from bit 0 of
+            // the tile index
+            let addr = if tall {
+                (tile as u16 & 0x01) * 0x1000
+                    + ((tile & 0xFE) as u16 + flipped_row as u16 / 8) * 16
+                    + flipped_row as u16 % 8
+            } else {
+                let table = if ppu.ctrl() & PpuCtrl::SPRITE_TABLE != 0 {
+                    0x1000
+                } else {
+                    0x0000
+                };
+
+                table + tile as u16 * 16 + flipped_row as u16
+            };
+
+            let mut pixels = tile_row(ppu, cartridge, addr);
+
+            if attributes & 0x40 != 0 {
+                pixels.reverse();
+            }
+
+            for (column, &pixel) in pixels.iter().enumerate() {
+                let color = if pixel == 0 {
+                    backdrop
+                } else {
+                    let entry = 0x10 + (attributes & 0x03) * 4 + pixel;
+                    palette_color(ppu, cartridge, palette, entry as u16)
+                };
+
+                image.set_pixel(x + column, y + row, color);
+            }
+        }
+    }
+
+    image
+}
+
+// Saves the pattern tables, the nametables and the sprites of the current state as PNG files in
+// dir. The pattern tables use the first background palette.
+pub fn save_viewers(nes: &Nes, palette: &Palette, dir: impl AsRef<Path>) -> Result<(), String> {
+    let dir = dir.as_ref();
+    let ppu = &nes.bus.ppu;
+    let cartridge = &nes.bus.cartridge;
+
+    let images = [
+        (
+            "pattern_tables.png",
+            pattern_tables(ppu, cartridge, palette, 0),
+        ),
+        ("name_tables.png", name_tables(ppu, cartridge, palette)),
+        ("sprites.png", sprites(ppu, cartridge, palette)),
+    ];
+
+    for (name, image) in images {
+        let path = dir.join(name);
+
+        image
+            .save_png(&path)
+            .map_err(|e| format!("Failed to save {}: {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,225 @@
+// Runs Tom Harte's SingleStepTests (ProcessorTests) nes6502 vectors against the CPU.
+//
+// The vectors are too large to live in the repository, so the test is ignored by default. Clone
+// https://github.com/SingleStepTests/ProcessorTests and run it with PROCESSOR_TESTS_DIR pointing
+// to its nes6502/v1 directory (00.json to ff.json):
+//
+//     PROCESSOR_TESTS_DIR=path/to/nes6502/v1 cargo test -- --ignored
+
+use boss_rush_nes::nes::bus::{CpuBus, FlatBus};
+use boss_rush_nes::nes::cpu::{Cpu, CpuState};
+use boss_rush_nes::nes::instructions::get_instruction;
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const DEFAULT_DIR: &str = "tests/ProcessorTests/nes6502/v1";
+
+// Failures printed per opcode file, the rest are only counted
+const MAX_REPORTED_FAILURES: usize = 5;
+
+#[derive(Deserialize)]
+struct Test {
+    name: String,
+    initial: State,
+    #[serde(rename = "final")]
+    expected: State,
+    cycles: Vec<(u16, u8, String)>,
+}
+
+#[derive(Deserialize)]
+struct State {
+    pc: u16,
+    s: u8,
+    a: u8,
+    x: u8,
+    y: u8,
+    p: u8,
+    ram: Vec<(u16, u8)>,
+}
+
+// Records every bus access so the cycle by cycle activity can be compared against the vectors
+#[derive(Default)]
+struct RecordingBus {
+    bus: FlatBus,
+    cycles: Vec<(u16, u8, String)>,
+}
+
+impl CpuBus for RecordingBus {
+    fn read(&mut self, addr: u16) -> u8 {
+        let data = self.bus.read(addr);
+        self.cycles.push((addr, data, "read".to_string()));
+
+        data
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.bus.write(addr, data);
+        self.cycles.push((addr, data, "write".to_string()));
+    }
+
+    fn peek(&self, addr: u16) -> u8 {
+        self.bus.peek(addr)
+    }
+}
+
+impl State {
+    // Break and unused are not stored in the status register
+    fn registers(&self) -> (u16, u8, u8, u8, u8, u8) {
+        (
+            self.pc,
+            self.s,
+            self.a,
+            self.x,
+            self.y,
+            self.p & 0b1100_1111,
+        )
+    }
+}
+
+fn run_test(test: &Test) -> Result<(), String> {
+    let mut bus = RecordingBus::default();
+    let mut cpu = Cpu::default();
+
+    for &(addr, value) in &test.initial.ram {
+        bus.bus.write(addr, value);
+    }
+
+    let State {
+        pc, s, a, x, y, p, ..
+    } = test.initial;
+
+    cpu.set_state(&CpuState {
+        a,
+        x,
+        y,
+        sp: s,
+        pc,
+        p,
+        opcode: 0,
+        cycles: 0,
+    });
+
+    let cycles = cpu.step(&mut bus).map_err(|jam| jam.to_string())?;
+
+    let state = cpu.get_state();
+    let actual = State {
+        pc: state.pc,
+        s: state.sp,
+        a: state.a,
+        x: state.x,
+        y: state.y,
+        p: state.p,
+        ram: vec![],
+    };
+
+    let mut errors = vec![];
+
+    if actual.registers() != test.expected.registers() {
+        errors.push(format!(
+            "registers (pc, s, a, x, y, p): expected {:02X?}, got {:02X?}",
+            test.expected.registers(),
+            actual.registers()
+        ));
+    }
+
+    for &(addr, value) in &test.expected.ram {
+        let actual = bus.peek(addr);
+
+        if actual != value {
+            errors.push(format!(
+                "ram ${:04X}: expected {:02X}, got {:02X}",
+                addr, value, actual
+            ));
+        }
+    }
+
+    if cycles as usize != test.cycles.len() {
+        errors.push(format!(
+            "cycles: expected {}, got {}",
+            test.cycles.len(),
+            cycles
+        ));
+    }
+
+    if let Some(i) = (0..test.cycles.len().max(bus.cycles.len()))
+        .find(|&i| test.cycles.get(i) != bus.cycles.get(i))
+    {
+        errors.push(format!(
+            "bus cycle {}: expected {:02X?}, got {:02X?}",
+            i + 1,
+            test.cycles.get(i),
+            bus.cycles.get(i)
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join(", "))
+    }
+}
+
+#[test]
+#[ignore = "needs the ProcessorTests vectors, see PROCESSOR_TESTS_DIR"]
+fn processor_tests() {
+    let dir = env::var("PROCESSOR_TESTS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_DIR));
+
+    assert!(
+        dir.is_dir(),
+        "ProcessorTests vectors not found in {} (set PROCESSOR_TESTS_DIR)",
+        dir.display()
+    );
+
+    let mut failed_opcodes = vec![];
+
+    for opcode in 0..=0xFFu8 {
+        // Jam opcodes halt the CPU instead of completing
+        if get_instruction(opcode).name == "jam" {
+            continue;
+        }
+
+        let path = dir.join(format!("{:02x}.json", opcode));
+
+        let Ok(json) = fs::read_to_string(&path) else {
+            eprintln!("Missing {}", path.display());
+            continue;
+        };
+
+        let tests: Vec<Test> = serde_json::from_str(&json)
+            .unwrap_or_else(|e| panic!("Invalid {}: {}", path.display(), e));
+
+        let failures: Vec<String> = tests
+            .iter()
+            .filter_map(|test| {
+                run_test(test)
+                    .err()
+                    .map(|error| format!("{}: {}", test.name, error))
+            })
+            .collect();
+
+        if !failures.is_empty() {
+            eprintln!(
+                "Opcode ${:02X}: {} of {} tests failed",
+                opcode,
+                failures.len(),
+                tests.len()
+            );
+
+            for failure in failures.iter().take(MAX_REPORTED_FAILURES) {
+                eprintln!("    {}", failure);
+            }
+
+            failed_opcodes.push(opcode);
+        }
+    }
+
+    assert!(
+        failed_opcodes.is_empty(),
+        "Failing opcodes: {:02X?}",
+        failed_opcodes
+    );
+}